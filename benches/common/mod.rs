@@ -0,0 +1,123 @@
+//! Helpers shared by the benchmarks, which time the `til` binary over a generated
+//! store of notes.
+
+// every benchmark compiles its own copy of this module and uses only part of it
+#![allow(dead_code)]
+
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use chrono::{Days, NaiveDate};
+
+const RUNS: usize = 5;
+const BULLETS_PER_NOTE: usize = 20;
+
+/// A temporary `TIL_HOME` holding notes for consecutive dates, removed on drop.
+pub struct Store {
+    pub home: PathBuf,
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl Store {
+    /// Writes `notes_per_day` notes with front matter for each of `days` consecutive
+    /// dates, the first of which is the "default" note of its date.
+    pub fn generate(days: u64, notes_per_day: usize) -> Self {
+        let home = env::temp_dir().join(format!("til-bench-{}", std::process::id()));
+        let first = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let bullets = (0..BULLETS_PER_NOTE)
+            .map(|index| format!("- bullet {index} about ownership, lifetimes and borrowing\n"))
+            .collect::<String>();
+
+        for offset in 0..days {
+            let directory = home
+                .join("notes")
+                .join((first + Days::new(offset)).format("%m-%d-%Y").to_string());
+            fs::create_dir_all(&directory).unwrap();
+
+            for index in 0..notes_per_day {
+                let title = match index {
+                    0 => "default".to_owned(),
+                    _ => format!("note-{index}"),
+                };
+                fs::write(
+                    directory.join(format!("{title}.md")),
+                    format!("---\ntitle: \"{title}\"\ntags: [rust]\n---\n\n{bullets}"),
+                )
+                .unwrap();
+            }
+        }
+
+        Store {
+            home,
+            first,
+            last: first + Days::new(days.saturating_sub(1)),
+        }
+    }
+
+    /// Runs `til` with the arguments a few times, printing its median and fastest
+    /// timings under the given name.
+    pub fn bench(&self, name: &str, args: &[&str]) {
+        self.bench_with_env(name, args, &[]);
+    }
+
+    /// Runs `til` like [`Store::bench`], with extra environment variables set.
+    pub fn bench_with_env(&self, name: &str, args: &[&str], vars: &[(&str, &str)]) {
+        bench(name, || self.run(args, vars));
+    }
+
+    /// Lists the path of every generated note.
+    pub fn notes(&self) -> Vec<PathBuf> {
+        fs::read_dir(self.home.join("notes"))
+            .unwrap()
+            .flat_map(|day| fs::read_dir(day.unwrap().path()).unwrap())
+            .map(|note| note.unwrap().path())
+            .collect()
+    }
+
+    fn run(&self, args: &[&str], vars: &[(&str, &str)]) {
+        let status = Command::new(env!("CARGO_BIN_EXE_til"))
+            .args(args)
+            .arg("--no-color")
+            .env("TIL_HOME", &self.home)
+            .envs(vars.iter().copied())
+            .stdout(Stdio::null())
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
+
+/// Runs a function a few times, printing its median and fastest timings under the
+/// given name.
+pub fn bench(name: &str, mut run: impl FnMut()) {
+    let mut timings = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .collect::<Vec<Duration>>();
+    timings.sort();
+
+    println!(
+        "{name}: median {:?}, fastest {:?}",
+        timings[RUNS / 2],
+        timings[0]
+    );
+}
+
+impl Drop for Store {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.home);
+    }
+}
+
+/// Returns the first numeric argument passed after `cargo bench --`, if any.
+pub fn size_from_args() -> Option<u64> {
+    env::args().skip(1).find_map(|arg| arg.parse().ok())
+}
@@ -0,0 +1,78 @@
+//! Times `til search --from ... --to ...` over a generated archive of notes.
+//!
+//! Run with `cargo bench --bench recall`, optionally passing the number of days to
+//! generate: `cargo bench --bench recall -- 10000`. The notes of a range are written
+//! as they are read, so the time grows with the size of the archive while memory
+//! stays flat.
+//!
+//! Next to the command, the two ways of printing a range are timed in process over
+//! the same notes: collecting every line before writing each one to an unbuffered
+//! file, as notes were printed before being streamed, and writing every line
+//! through a `BufWriter` as its note is read.
+
+mod common;
+
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use common::Store;
+
+fn main() {
+    let days = common::size_from_args().unwrap_or(5000);
+    let store = Store::generate(days, 1);
+    let from = store.first.format("%m-%d-%Y").to_string();
+    let to = store.last.format("%m-%d-%Y").to_string();
+
+    let range = ["search", "--from", &from, "--to", &to];
+    store.bench(&format!("recall {days} days as text"), &range);
+    store.bench(
+        &format!("recall {days} days as json"),
+        &[&range[..], &["--json"]].concat(),
+    );
+
+    let notes = store.notes();
+    let output = env::temp_dir().join(format!("til-bench-recall-{}.md", std::process::id()));
+    common::bench(
+        &format!("print {days} days collected first, unbuffered (baseline)"),
+        || collected(&notes, &output),
+    );
+    common::bench(
+        &format!("print {days} days streamed through a BufWriter"),
+        || streamed(&notes, &output),
+    );
+    let _ = fs::remove_file(output);
+}
+
+/// Reads every note into a list of lines, then writes the lines one at a time.
+fn collected(notes: &[impl AsRef<Path>], output: &Path) {
+    let lines = notes
+        .iter()
+        .flat_map(|note| {
+            fs::read_to_string(note)
+                .unwrap()
+                .lines()
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut file = File::create(output).unwrap();
+    for line in lines {
+        writeln!(file, "{line}").unwrap();
+    }
+}
+
+/// Writes the lines of every note as soon as it is read, through a `BufWriter`.
+fn streamed(notes: &[impl AsRef<Path>], output: &Path) {
+    let mut out = BufWriter::new(File::create(output).unwrap());
+    for note in notes {
+        for line in fs::read_to_string(note).unwrap().lines() {
+            writeln!(out, "{line}").unwrap();
+        }
+    }
+    out.flush().unwrap();
+}
@@ -0,0 +1,33 @@
+//! Times `til find` and `til grep` over a generated store of 10,000 notes.
+//!
+//! Run with `cargo bench --bench search`, optionally passing the number of notes
+//! to generate: `cargo bench --bench search -- 50000`. Notes are read and matched
+//! in parallel, so the time shrinks with the number of cores. Every search is also
+//! timed on a single thread, with `RAYON_NUM_THREADS=1`, as the sequential baseline.
+
+mod common;
+
+use common::Store;
+
+const NOTES_PER_DAY: usize = 10;
+const SEQUENTIAL: [(&str, &str); 1] = [("RAYON_NUM_THREADS", "1")];
+
+fn main() {
+    let notes = common::size_from_args().unwrap_or(10_000);
+    let store = Store::generate(notes.div_ceil(NOTES_PER_DAY as u64), NOTES_PER_DAY);
+
+    let searches: [(&str, &[&str]); 4] = [
+        ("find", &["find", "bullet 19"]),
+        ("find ignoring case", &["find", "-i", "LIFETIMES"]),
+        ("grep", &["grep", r"bullet 1\d about"]),
+        ("grep --count", &["grep", "--count", "borrow(ing)?$"]),
+    ];
+    for (name, args) in searches {
+        store.bench_with_env(
+            &format!("{name} over {notes} notes, sequential (baseline)"),
+            args,
+            &SEQUENTIAL,
+        );
+        store.bench(&format!("{name} over {notes} notes, parallel"), args);
+    }
+}
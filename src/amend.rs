@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{config::Config, date::parse_date, error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Amend {
+    /// The content replacing the last bullet of the note
+    pub content: String,
+
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+}
+
+impl Amend {
+    /// Replaces the last bullet of a note with new content, keeping its front
+    /// matter and every earlier bullet as is.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::NoContent` - If the new content is empty.
+    /// * `Error::CannotFindFile` - If the note does not exist.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::CannotDecrypt` - If the note is encrypted and cannot be decrypted.
+    /// * `Error::NoBulletToAmend` - If the note has no bullet.
+    /// * `Error::CannotWriteToFile` - If the amended note cannot be written.
+    pub fn amend(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let config = Config::load()?;
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        let path = notes::note_path(root_dir, date, title)?;
+
+        if self.content.trim().is_empty() {
+            return Err(Error::NoContent);
+        }
+        if !path.exists() {
+            return Err(Error::CannotFindFile(path));
+        }
+
+        let (contents, sealed) = notes::read_sealed(&path)?;
+        let amended = amend_last_bullet(&contents, self.content.trim())
+            .ok_or_else(|| Error::NoBulletToAmend(path.clone()))?;
+
+        notes::write_note(root_dir, &path, &amended, sealed)
+    }
+}
+
+/// Replaces the last bullet within the body of a note, returning `None` when the
+/// note has no bullet.
+fn amend_last_bullet(contents: &str, content: &str) -> Option<String> {
+    let body_start = contents.len() - notes::body(contents).len();
+    let mut lines: Vec<&str> = contents[body_start..].split_inclusive('\n').collect();
+    let last = lines
+        .iter()
+        .rposition(|line| notes::strip_bullet(line).is_some())?;
+    let bullet = notes::strip_bullet(lines[last]).unwrap_or_default();
+    let prefix = &lines[last][..lines[last].len() - bullet.len()];
+
+    let bullet = format!("{prefix}{content}\n");
+    lines[last] = &bullet;
+
+    Some(format!("{}{}", &contents[..body_start], lines.concat()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::amend_last_bullet;
+
+    #[test]
+    fn only_the_last_bullet_is_replaced() {
+        let contents = "---\ntitle: \"default\"\ntags: []\n---\n\n- first\n- tpyo\n";
+
+        assert_eq!(
+            amend_last_bullet(contents, "typo").unwrap(),
+            "---\ntitle: \"default\"\ntags: []\n---\n\n- first\n- typo\n"
+        );
+        assert!(amend_last_bullet("---\ntitle: \"default\"\n---\n\n", "typo").is_none());
+    }
+}
@@ -0,0 +1,141 @@
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use clap::Args;
+
+use crate::{
+    date::parse_date,
+    error::Error,
+    notes::{self, Day},
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Archive {
+    /// Archive the date directories before this date ("MM-DD-YYYY", "1 year ago", ...)
+    #[clap(long)]
+    pub before: String,
+}
+
+impl Archive {
+    /// Moves every date directory before the cutoff into the `archive` directory
+    /// within the notes directory, returning a line per archived directory followed
+    /// by a summary. With `dry_run`, the directories are only listed.
+    ///
+    /// Archived notes are left out of every command reading the notes, except
+    /// `search` with `--include-archived`. Nothing is moved when a directory is
+    /// already archived under the same name.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the cutoff cannot be parsed.
+    /// * `Error::CannotReadFile` - If the notes directory cannot be read.
+    /// * `Error::AlreadyArchived` - If the archive already holds a directory of the same name.
+    /// * `Error::CannotCreateDir` - If the archive directory cannot be created.
+    /// * `Error::CannotWriteToFile` - If a directory cannot be moved.
+    pub fn archive(&self, root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+        let cutoff = parse_date(&self.before)?;
+        archive_in(root_dir, cutoff, dry_run)
+    }
+}
+
+/// Archives the date directories of a notes directory before the cutoff, like
+/// [`Archive::archive`].
+fn archive_in(
+    root_dir: &Path,
+    cutoff: NaiveDate,
+    dry_run: bool,
+) -> crate::error::Result<Vec<String>> {
+    let archive_dir = notes::archive_dir_in(root_dir);
+
+    let days = notes::days(root_dir)?
+        .into_iter()
+        .filter(|day| day.date < cutoff)
+        .collect::<Vec<Day>>();
+    if let Some(target) = days
+        .iter()
+        .map(|day| archive_dir.join(day.name()))
+        .find(|target| target.exists())
+    {
+        return Err(Error::AlreadyArchived(target));
+    }
+
+    if !dry_run && !days.is_empty() {
+        fs::create_dir_all(&archive_dir)
+            .map_err(|err| Error::CannotCreateDir(archive_dir.display().to_string(), err))?;
+    }
+
+    let verb = if dry_run { "would archive" } else { "archived" };
+    let mut lines = Vec::new();
+    for day in &days {
+        if !dry_run {
+            fs::rename(&day.path, archive_dir.join(day.name()))
+                .map_err(|err| Error::CannotWriteToFile(day.path.clone(), err))?;
+        }
+        lines.push(format!("{verb} {}", day.name()));
+    }
+    lines.push(format!("{verb} {} date(s)", days.len()));
+
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::archive_in;
+    use crate::{date::parse_date, error::Error};
+
+    #[test]
+    fn dates_before_the_cutoff_are_archived_once() {
+        let root_dir = env::temp_dir().join(format!("til-archive-{}", std::process::id()));
+        for name in ["01-15-2024", "02-29-2024", "03-01-2024"] {
+            fs::create_dir_all(root_dir.join(name)).unwrap();
+            fs::write(root_dir.join(name).join("default.md"), "- note\n").unwrap();
+        }
+        let cutoff = parse_date("03-01-2024").unwrap();
+
+        let listed = archive_in(&root_dir, cutoff, true).unwrap();
+        let still_there = root_dir.join("01-15-2024").exists();
+        let archived = archive_in(&root_dir, cutoff, false).unwrap();
+        let moved = ["01-15-2024", "02-29-2024"].map(|name| {
+            root_dir
+                .join("archive")
+                .join(name)
+                .join("default.md")
+                .exists()
+        });
+        let kept = root_dir.join("03-01-2024").exists();
+
+        fs::create_dir_all(root_dir.join("01-15-2024")).unwrap();
+        let refused = archive_in(&root_dir, cutoff, false);
+        let left_alone = root_dir.join("01-15-2024").exists();
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert_eq!(
+            listed,
+            [
+                "would archive 01-15-2024",
+                "would archive 02-29-2024",
+                "would archive 2 date(s)"
+            ]
+        );
+        assert!(still_there);
+        assert_eq!(
+            archived,
+            [
+                "archived 01-15-2024",
+                "archived 02-29-2024",
+                "archived 2 date(s)"
+            ]
+        );
+        assert_eq!(moved, [true, true]);
+        assert!(kept);
+        assert!(matches!(
+            refused,
+            Err(Error::AlreadyArchived(target))
+                if target == root_dir.join("archive").join("01-15-2024")
+        ));
+        assert!(left_alone);
+    }
+}
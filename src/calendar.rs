@@ -0,0 +1,98 @@
+use std::{collections::HashMap, path::Path};
+
+use chrono::{Datelike, Local, NaiveDate};
+use clap::Args;
+
+use crate::notes;
+
+const WEEKDAYS: &str = "Mo Tu We Th Fr Sa Su";
+const SHADES: [&str; 5] = ["· ", "░░", "▒▒", "▓▓", "██"];
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Calendar {
+    /// Show this year instead of the current one
+    #[clap(long)]
+    pub year: Option<i32>,
+}
+
+impl Calendar {
+    /// Lays out every month of the year as a grid of weeks, shading each day by
+    /// the number of notes written on it.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If the notes directory or a date directory cannot be read.
+    pub fn render(&self, root_dir: &Path) -> crate::error::Result<String> {
+        let year = self.year.unwrap_or_else(|| Local::now().year());
+        let mut counts = HashMap::new();
+
+        for day in notes::days(root_dir)?
+            .into_iter()
+            .filter(|day| day.date.year() == year)
+        {
+            *counts.entry(day.date).or_insert(0) += notes::notes_in(&day.path)?.len();
+        }
+
+        let months = (1..=12)
+            .filter_map(|month| month_grid(year, month, &counts))
+            .map(|grid| grid.join("\n"))
+            .collect::<Vec<_>>();
+        let legend = format!(
+            "less {} more",
+            SHADES
+                .iter()
+                .map(|shade| shade.to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+
+        Ok(format!("{}\n\n{legend}", months.join("\n\n")))
+    }
+}
+
+/// Lays out a month as a title, a weekday header and a row per week starting on
+/// Monday, each day shaded by its number of notes.
+fn month_grid(year: i32, month: u32, counts: &HashMap<NaiveDate, usize>) -> Option<Vec<String>> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let mut lines = vec![first.format("%B %Y").to_string(), WEEKDAYS.to_owned()];
+
+    let mut week = vec!["  "; first.weekday().num_days_from_monday() as usize];
+    for date in first.iter_days().take_while(|date| date.month() == month) {
+        let count = counts.get(&date).copied().unwrap_or(0);
+        week.push(SHADES[count.min(SHADES.len() - 1)]);
+
+        if week.len() == 7 {
+            lines.push(week.join(" ").trim_end().to_owned());
+            week.clear();
+        }
+    }
+    if !week.is_empty() {
+        lines.push(week.join(" ").trim_end().to_owned());
+    }
+
+    Some(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveDate;
+
+    use super::month_grid;
+
+    #[test]
+    fn months_start_on_their_weekday() {
+        let counts = HashMap::from([
+            (NaiveDate::from_ymd_opt(2024, 10, 1).unwrap(), 1),
+            (NaiveDate::from_ymd_opt(2024, 10, 7).unwrap(), 9),
+        ]);
+        let grid = month_grid(2024, 10, &counts).unwrap();
+
+        assert_eq!(grid[0], "October 2024");
+        assert_eq!(grid[2], "   ░░ ·  ·  ·  ·  ·");
+        assert_eq!(grid[3], "██ ·  ·  ·  ·  ·  ·");
+        assert_eq!(grid.len(), 7);
+    }
+}
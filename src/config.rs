@@ -0,0 +1,257 @@
+use std::{fs, path::PathBuf};
+
+use chrono::NaiveDate;
+use clap::{Subcommand, ValueEnum};
+use serde::Deserialize;
+
+use crate::{date::validate_format, error::Error, find_home_dir, notes};
+
+const CONFIG_FILE: &str = "config.toml";
+const DEFAULT_TITLE: &str = "default";
+const DEFAULT_TAG_DELIMITER: char = ',';
+
+/// The config file written by `til init`, with every field commented out.
+pub const TEMPLATE: &str = r#"# Store notes here instead of ~/.til/notes, ignored when TIL_HOME or --notes-dir is set
+# root = "~/Documents/til"
+
+# Title used when --title is omitted
+# default_title = "default"
+
+# Always prefix bullets with the time they were added
+# timestamp = false
+
+# Open notes with this instead of $EDITOR
+# editor = "vi"
+
+# Separate the tags passed with --tags with this instead of a comma
+# tag_delimiter = ","
+
+# Start bullets with this instead of "- ", e.g. "* ", or "1. " for a numbered list
+# bullet_prefix = "- "
+
+# Treat tags differing only by case, e.g. "Rust" and "rust", as the same tag, writing
+# them in lowercase, run `til merge-tags Rust --into rust` to merge the existing ones
+# case_insensitive_tags = false
+
+# Commit the notes directory after every note, when it's within a git repository
+# git = false
+
+# Name the directory of each date "MM-DD-YYYY", or "YYYY-MM-DD" to sort them by date,
+# run `til migrate-dates` after changing it to rename the existing directories
+# date_format = "MM-DD-YYYY"
+
+# Display dates in this chrono format, e.g. "%b %-d, %Y" for "Oct 5, 2024", leaving the
+# names of the date directories as they are
+# display_date_format = "%m-%d-%Y"
+
+# Render the front matter of new notes from this file, holding at least {{title}} and
+# {{tags}}, along with {{date}} and {{created}} or any field of your own
+# template = "~/.til/template.yml"
+
+# Encrypt the body of every note written with a passphrase, read from TIL_PASSPHRASE
+# or prompted for, leaving titles and tags readable
+# encrypt = false
+"#;
+
+/// Settings read from `~/.til/config.toml`, falling back to built-in defaults for
+/// anything left out.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Directory to store notes in, instead of `~/.til/notes`.
+    pub root: Option<PathBuf>,
+
+    /// Title of notes written or searched without one, instead of "default".
+    pub default_title: Option<String>,
+
+    /// Whether bullets are always prefixed with the time they were added.
+    pub timestamp: bool,
+
+    /// Command used to open notes, instead of `$EDITOR`.
+    pub editor: Option<String>,
+
+    /// Character separating the tags passed with `--tags`, instead of a comma.
+    pub tag_delimiter: Option<char>,
+
+    /// Whether tags are written in lowercase and told apart regardless of case.
+    pub case_insensitive_tags: bool,
+
+    /// What bullets start with, "- " unless configured.
+    pub bullet_prefix: Option<String>,
+
+    /// Whether notes are committed to git after being written, when the notes
+    /// directory is within a git repository.
+    pub git: bool,
+
+    /// How the directory of each date is named, "MM-DD-YYYY" unless configured.
+    pub date_format: DateFormat,
+
+    /// Format dates are displayed in, instead of the name of their directory.
+    pub display_date_format: Option<String>,
+
+    /// File the front matter of new notes is rendered from, instead of the built-in one.
+    pub template: Option<PathBuf>,
+
+    /// Whether the body of notes is encrypted with a passphrase when written.
+    pub encrypt: bool,
+}
+
+/// How the directory holding the notes of a date is named.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, ValueEnum)]
+pub enum DateFormat {
+    /// Month first, e.g. "08-18-2024", the format notes have always been stored in
+    #[default]
+    #[serde(rename = "MM-DD-YYYY")]
+    #[value(name = "MM-DD-YYYY")]
+    Legacy,
+
+    /// Year first, e.g. "2024-08-18", which sorts chronologically
+    #[serde(rename = "YYYY-MM-DD")]
+    #[value(name = "YYYY-MM-DD")]
+    Iso,
+}
+
+impl DateFormat {
+    /// Returns the name of the directory of a date in this format.
+    pub fn format(self, date: NaiveDate) -> String {
+        date.format(self.pattern()).to_string()
+    }
+
+    /// Returns how the format is written in the config, e.g. "MM-DD-YYYY".
+    pub fn name(self) -> &'static str {
+        match self {
+            DateFormat::Legacy => "MM-DD-YYYY",
+            DateFormat::Iso => "YYYY-MM-DD",
+        }
+    }
+
+    /// Finds which format the name of a date directory is in, along with its date.
+    pub fn detect(name: &str) -> Option<(DateFormat, NaiveDate)> {
+        DateFormat::value_variants().iter().find_map(|&format| {
+            NaiveDate::parse_from_str(name, format.pattern())
+                .ok()
+                .map(|date| (format, date))
+        })
+    }
+
+    fn pattern(self) -> &'static str {
+        match self {
+            DateFormat::Legacy => "%m-%d-%Y",
+            DateFormat::Iso => "%Y-%m-%d",
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config file, using the built-in defaults when it doesn't exist.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotReadFile` - If the config file cannot be read.
+    /// * `Error::CannotParseConfig` - If the config file is malformed, or its display date format or bullet prefix is invalid.
+    pub fn load() -> crate::error::Result<Config> {
+        let path = config_path()?;
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents =
+            fs::read_to_string(&path).map_err(|err| Error::CannotReadFile(path.clone(), err))?;
+
+        let config: Config = toml::from_str(&contents)
+            .map_err(|err| Error::CannotParseConfig(path.clone(), err.message().to_owned()))?;
+        if let Some(format) = &config.display_date_format {
+            validate_format(format)
+                .map_err(|err| Error::CannotParseConfig(path.clone(), err.to_string()))?;
+        }
+        if let Some(prefix) = &config.bullet_prefix {
+            notes::validate_bullet_prefix(prefix)
+                .map_err(|err| Error::CannotParseConfig(path, err.to_string()))?;
+        }
+
+        Ok(config)
+    }
+
+    /// Returns the character separating the tags passed with `--tags`.
+    pub fn tag_delimiter(&self) -> char {
+        self.tag_delimiter.unwrap_or(DEFAULT_TAG_DELIMITER)
+    }
+
+    /// Returns the front matter template, with a leading `~` expanded to the home
+    /// directory.
+    pub fn template(&self) -> Option<PathBuf> {
+        let template = self.template.as_ref()?;
+        match (template.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(relative), Some(home)) => Some(home.join(relative)),
+            _ => Some(template.clone()),
+        }
+    }
+
+    /// Returns the title of notes written or searched without one.
+    pub fn default_title(&self) -> &str {
+        self.default_title.as_deref().unwrap_or(DEFAULT_TITLE)
+    }
+}
+
+/// Returns the path of the config file, e.g. `~/.til/config.toml`. The file
+/// doesn't need to exist.
+pub fn config_path() -> crate::error::Result<PathBuf> {
+    let home_dir = find_home_dir().ok_or(Error::CannotFindDir("home".to_owned()))?;
+    Ok(home_dir.join(CONFIG_FILE))
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print where the config file lives
+    Path,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{Config, DateFormat};
+
+    #[test]
+    fn missing_fields_use_defaults() {
+        let config: Config = toml::from_str("timestamp = true").unwrap();
+
+        assert!(config.timestamp);
+        assert!(config.root.is_none());
+        assert_eq!(config.default_title(), "default");
+    }
+
+    #[test]
+    fn template_is_the_default_config() {
+        let config: Config = toml::from_str(super::TEMPLATE).unwrap();
+
+        assert!(!config.timestamp && !config.git);
+        assert!(config.editor.is_none());
+    }
+
+    #[test]
+    fn date_formats_round_trip() {
+        let config: Config = toml::from_str("date_format = \"YYYY-MM-DD\"").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 8, 1).unwrap();
+
+        assert_eq!(config.date_format.format(date), "2024-08-01");
+        assert_eq!(DateFormat::Legacy.format(date), "08-01-2024");
+        assert_eq!(
+            DateFormat::detect("2024-08-01"),
+            Some((DateFormat::Iso, date))
+        );
+        assert_eq!(
+            DateFormat::detect("8-1-2024"),
+            Some((DateFormat::Legacy, date))
+        );
+        assert!(DateFormat::detect("drafts").is_none());
+        assert!(toml::from_str::<Config>("date_format = \"DD-MM-YYYY\"").is_err());
+    }
+
+    #[test]
+    fn unknown_fields_are_rejected() {
+        assert!(toml::from_str::<Config>("titel = \"oops\"").is_err());
+    }
+}
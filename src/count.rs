@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::{date::parse_date, index::Index, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Count {
+    /// Count the notes carrying this tag instead of bullets
+    #[clap(long)]
+    pub tag: Option<String>,
+
+    /// Only count within this date ("MM-DD-YYYY", "yesterday", ...)
+    #[clap(long)]
+    pub date: Option<String>,
+}
+
+impl Count {
+    /// Counts the bullets across every note, or within a single date with `--date`.
+    /// With `--tag`, the notes carrying the tag are counted instead, ignoring case.
+    ///
+    /// Tags are read from the index, so that counting them never reads, nor
+    /// decrypts, a note. A date without notes counts zero.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    pub fn total(&self, root_dir: &Path) -> crate::error::Result<usize> {
+        if let Some(tag) = &self.tag {
+            let tag = tag.trim().to_lowercase();
+            let date = self.date.as_deref().map(parse_date).transpose()?;
+
+            return Ok(Index::load(root_dir)?
+                .notes
+                .iter()
+                .filter(|note| date.is_none_or(|date| note.date == date))
+                .filter(|note| note.tags.iter().any(|found| found.to_lowercase() == tag))
+                .count());
+        }
+
+        let directories = match &self.date {
+            Some(date) => {
+                let directory = notes::day_path(root_dir, parse_date(date)?)?;
+                if directory.exists() {
+                    vec![directory]
+                } else {
+                    Vec::new()
+                }
+            }
+            None => notes::days(root_dir)?
+                .into_iter()
+                .map(|day| day.path)
+                .collect(),
+        };
+
+        let mut total = 0;
+        for directory in directories {
+            for path in notes::notes_in(&directory)? {
+                total += count_bullets(&notes::read(&path)?);
+            }
+        }
+
+        Ok(total)
+    }
+}
+
+/// Counts the bullets within the body of a note without parsing its front matter.
+fn count_bullets(contents: &str) -> usize {
+    notes::body(contents)
+        .lines()
+        .filter(|line| notes::strip_bullet(line).is_some())
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::count_bullets;
+
+    #[test]
+    fn bullets_are_counted_outside_front_matter() {
+        let contents = "---\ntitle: \"default\"\ntags:\n- rust\n---\n\n- one\n  - nested\n- two\n";
+
+        assert_eq!(count_bullets(contents), 2);
+        assert_eq!(count_bullets(""), 0);
+    }
+}
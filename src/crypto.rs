@@ -0,0 +1,274 @@
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::Path,
+    sync::{LazyLock, Mutex, OnceLock},
+};
+
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::{aead::Aead, Key, KeyInit, XChaCha20Poly1305, XNonce};
+
+use crate::{error::Error, front_matter::FrontMatter};
+
+const PASSPHRASE_VAR: &str = "TIL_PASSPHRASE";
+/// The file within the notes directory holding the salt new notes are encrypted with.
+const SALT_FILE: &str = ".salt";
+/// The file next to the salt holding [`KEY_CHECK`] encrypted with the key of the
+/// passphrase, so that a mistyped passphrase is caught before encrypting a note.
+const KEY_CHECK_FILE: &str = ".key-check";
+const KEY_CHECK: &str = "til key check\n";
+
+const BEGIN: &str = "-----BEGIN TIL ENCRYPTED NOTE-----";
+const END: &str = "-----END TIL ENCRYPTED NOTE-----";
+const VERSION: u8 = 1;
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+const LINE_WIDTH: usize = 64;
+
+/// The keys derived from the passphrase so far, by salt, as deriving one is slow on
+/// purpose.
+static KEYS: LazyLock<Mutex<HashMap<[u8; SALT_LEN], Key>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Checks whether a note holds an encrypted body.
+pub fn is_sealed(contents: &str) -> bool {
+    contents.lines().any(|line| line == BEGIN)
+}
+
+/// Encrypts the body of a note, leaving its front matter readable so that its title
+/// and tags can still be listed. A note without front matter is encrypted whole.
+///
+/// The body is encrypted with XChaCha20-Poly1305 under a key derived from the
+/// passphrase with Argon2id, using the salt kept within the notes directory. The
+/// salt and nonce are stored along with the encrypted body, so a note can be
+/// decrypted on its own.
+///
+/// The key is checked against the key check kept next to the salt, which is
+/// created along with it, so that a note is never encrypted with another
+/// passphrase than the previous ones.
+///
+/// ## Errors
+///
+/// * `Error::NoPassphrase` - If no passphrase is set and none can be prompted for.
+/// * `Error::WrongPassphrase` - If the passphrase isn't the one of the key check.
+/// * `Error::CannotWriteToFile` - If a new salt or key check cannot be saved.
+pub fn seal(contents: &str, root_dir: &Path) -> crate::error::Result<String> {
+    let (salt, is_new) = store_salt(root_dir)?;
+    let check_path = root_dir.join(KEY_CHECK_FILE);
+    // A key check made for a replaced salt can no longer be opened.
+    let check = fs::read_to_string(&check_path).ok().filter(|_| !is_new);
+    let passphrase = passphrase(check.is_none())?;
+    let key = cached_key(passphrase, &salt)?;
+
+    match check {
+        Some(check) if !key_matches(&check, &key) => return Err(Error::WrongPassphrase),
+        Some(_) => {}
+        None => fs::write(&check_path, seal_with(KEY_CHECK, &key, &salt)?)
+            .map_err(|err| Error::CannotWriteToFile(check_path, err))?,
+    }
+
+    seal_with(contents, &key, &salt)
+}
+
+/// Decrypts the body of a note encrypted with [`seal`], returning the note as it
+/// was before being encrypted.
+///
+/// ## Errors
+///
+/// * `Error::NoPassphrase` - If no passphrase is set and none can be prompted for.
+/// * `Error::CannotDecrypt` - If the passphrase is wrong or the note was tampered with.
+pub fn open(contents: &str, path: &Path) -> crate::error::Result<String> {
+    let salt = sealed_salt(contents).ok_or_else(|| Error::CannotDecrypt(path.to_path_buf()))?;
+    let key = cached_key(passphrase(false)?, &salt)?;
+
+    open_with(contents, &key).ok_or_else(|| Error::CannotDecrypt(path.to_path_buf()))
+}
+
+fn seal_with(contents: &str, key: &Key, salt: &[u8; SALT_LEN]) -> crate::error::Result<String> {
+    let body = FrontMatter::split(contents).map_or(contents, |(_, body)| body);
+    let front_matter = &contents[..contents.len() - body.len()];
+
+    let nonce = XNonce::from(rand::random::<[u8; NONCE_LEN]>());
+    let encrypted = XChaCha20Poly1305::new(key)
+        .encrypt(&nonce, body.as_bytes())
+        .map_err(|err| Error::Custom(format!("cannot encrypt note: {err}")))?;
+
+    let mut payload = vec![VERSION];
+    payload.extend_from_slice(salt);
+    payload.extend_from_slice(&nonce);
+    payload.extend_from_slice(&encrypted);
+
+    let encoded = STANDARD.encode(payload);
+    let lines = encoded
+        .as_bytes()
+        .chunks(LINE_WIDTH)
+        .map(|line| String::from_utf8_lossy(line).into_owned())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(format!("{front_matter}{BEGIN}\n{lines}\n{END}\n"))
+}
+
+/// Decrypts a sealed note, returning `None` when the key is wrong or the note
+/// isn't sealed or was tampered with.
+fn open_with(contents: &str, key: &Key) -> Option<String> {
+    let (front_matter, payload) = sealed_payload(contents)?;
+    let nonce = XNonce::try_from(&payload[1 + SALT_LEN..1 + SALT_LEN + NONCE_LEN]).ok()?;
+
+    let body = XChaCha20Poly1305::new(key)
+        .decrypt(&nonce, &payload[1 + SALT_LEN + NONCE_LEN..])
+        .ok()?;
+
+    Some(format!("{front_matter}{}", String::from_utf8(body).ok()?))
+}
+
+/// Splits a sealed note into what precedes its encrypted block and the decoded
+/// payload of the block, checking its version and length.
+fn sealed_payload(contents: &str) -> Option<(&str, Vec<u8>)> {
+    let start = contents.find(&format!("{BEGIN}\n"))?;
+    let block = &contents[start + BEGIN.len() + 1..];
+    let block = &block[..block.find(END)?];
+
+    let encoded = block.split_whitespace().collect::<String>();
+    let payload = STANDARD.decode(encoded).ok()?;
+    if payload.len() <= 1 + SALT_LEN + NONCE_LEN || payload[0] != VERSION {
+        return None;
+    }
+
+    Some((&contents[..start], payload))
+}
+
+/// Checks whether a key opens a key check.
+fn key_matches(check: &str, key: &Key) -> bool {
+    open_with(check, key).as_deref() == Some(KEY_CHECK)
+}
+
+fn sealed_salt(contents: &str) -> Option<[u8; SALT_LEN]> {
+    let (_, payload) = sealed_payload(contents)?;
+    payload[1..1 + SALT_LEN].try_into().ok()
+}
+
+/// Derives the key of a passphrase and salt with Argon2id.
+fn derive_key(passphrase: &str, salt: &[u8; SALT_LEN]) -> crate::error::Result<Key> {
+    let mut key = [0; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|err| Error::Custom(format!("cannot derive key: {err}")))?;
+
+    Ok(Key::from(key))
+}
+
+fn cached_key(passphrase: &str, salt: &[u8; SALT_LEN]) -> crate::error::Result<Key> {
+    let mut keys = KEYS.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(key) = keys.get(salt) {
+        return Ok(*key);
+    }
+
+    let key = derive_key(passphrase, salt)?;
+    keys.insert(*salt, key);
+
+    Ok(key)
+}
+
+/// Returns the passphrase from `TIL_PASSPHRASE`, or prompts for it once per run.
+/// With `confirm`, a prompted passphrase must be entered twice, so that a typo doesn't
+/// end up in the key check and lock the first encrypted notes away.
+fn passphrase(confirm: bool) -> crate::error::Result<&'static str> {
+    static PASSPHRASE: OnceLock<String> = OnceLock::new();
+    if let Some(passphrase) = PASSPHRASE.get() {
+        return Ok(passphrase);
+    }
+
+    let passphrase = match env::var(PASSPHRASE_VAR) {
+        Ok(passphrase) if !passphrase.is_empty() => passphrase,
+        _ => {
+            let passphrase =
+                rpassword::prompt_password("passphrase: ").map_err(|_| Error::NoPassphrase)?;
+            if confirm
+                && rpassword::prompt_password("confirm passphrase: ")
+                    .map_err(|_| Error::NoPassphrase)?
+                    != passphrase
+            {
+                return Err(Error::Custom("the passphrases don't match".to_owned()));
+            }
+            passphrase
+        }
+    };
+    if passphrase.is_empty() {
+        return Err(Error::NoPassphrase);
+    }
+
+    Ok(PASSPHRASE.get_or_init(|| passphrase))
+}
+
+/// Returns the salt new notes are encrypted with, along with whether it was just
+/// created. A missing or malformed salt is replaced, which only costs deriving
+/// another key, as every note keeps the salt it was encrypted with.
+fn store_salt(root_dir: &Path) -> crate::error::Result<([u8; SALT_LEN], bool)> {
+    let path = root_dir.join(SALT_FILE);
+    if let Some(salt) = fs::read(&path)
+        .ok()
+        .and_then(|salt| <[u8; SALT_LEN]>::try_from(salt).ok())
+    {
+        return Ok((salt, false));
+    }
+
+    let salt = rand::random::<[u8; SALT_LEN]>();
+    fs::write(&path, salt).map_err(|err| Error::CannotWriteToFile(path, err))?;
+
+    Ok((salt, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_key, is_sealed, key_matches, open_with, seal_with, sealed_salt, KEY_CHECK};
+
+    #[test]
+    fn sealed_notes_keep_their_front_matter_readable() {
+        let salt = [7; 16];
+        let key = derive_key("correct horse", &salt).unwrap();
+        let contents = "---\ntitle: \"secret\"\ntags: [private]\n---\n\n- the plan\n";
+
+        let sealed = seal_with(contents, &key, &salt).unwrap();
+        assert!(sealed.starts_with("---\ntitle: \"secret\"\ntags: [private]\n---\n-----BEGIN"));
+        assert!(!sealed.contains("the plan"));
+        assert!(is_sealed(&sealed));
+        assert!(!is_sealed(contents));
+        assert_eq!(sealed_salt(&sealed), Some(salt));
+
+        assert_eq!(open_with(&sealed, &key).as_deref(), Some(contents));
+        assert_ne!(seal_with(contents, &key, &salt).unwrap(), sealed);
+    }
+
+    #[test]
+    fn wrong_keys_and_tampering_are_rejected() {
+        let salt = [7; 16];
+        let key = derive_key("correct horse", &salt).unwrap();
+        let wrong = derive_key("battery staple", &salt).unwrap();
+        let sealed = seal_with("- plain text note\n", &key, &salt).unwrap();
+
+        assert!(open_with(&sealed, &wrong).is_none());
+
+        let mut lines = sealed.lines().map(str::to_owned).collect::<Vec<_>>();
+        let last = lines.len() - 2;
+        lines[last] = lines[last].chars().rev().collect();
+        assert!(open_with(&lines.join("\n"), &key).is_none());
+        assert!(open_with("- not sealed\n", &key).is_none());
+    }
+
+    #[test]
+    fn key_checks_tell_mistyped_passphrases_apart() {
+        let salt = [7; 16];
+        let key = derive_key("correct horse", &salt).unwrap();
+        let typo = derive_key("correct hrose", &salt).unwrap();
+        let check = seal_with(KEY_CHECK, &key, &salt).unwrap();
+
+        assert!(key_matches(&check, &key));
+        assert!(!key_matches(&check, &typo));
+        assert!(!key_matches(
+            &seal_with("- a note\n", &key, &salt).unwrap(),
+            &key
+        ));
+    }
+}
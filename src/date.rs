@@ -0,0 +1,187 @@
+use std::{fmt::Write, sync::OnceLock};
+
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
+
+use crate::{config::Config, error::Error};
+
+/// The format dates are displayed in for this run, when one is set.
+static DISPLAY_FORMAT: OnceLock<Option<String>> = OnceLock::new();
+
+/// Parses a date as entered by the user.
+///
+/// Besides the format MM-DD-YYYY, this accepts ISO dates (YYYY-MM-DD), `today`,
+/// `yesterday`, `N days ago` and weekday names such as `monday` or `last monday`.
+/// A weekday always refers to its most recent occurrence before today, so
+/// `last monday` on a Monday means seven days ago.
+///
+/// ## Errors
+///
+/// * `Error::CannotParseDate` - If the date doesn't match any of the supported formats.
+pub fn parse_date(date: &str) -> crate::error::Result<NaiveDate> {
+    parse_date_from(date, Local::now().date_naive())
+}
+
+fn parse_date_from(date: &str, today: NaiveDate) -> crate::error::Result<NaiveDate> {
+    let err = || Error::CannotParseDate(date.to_owned());
+    let input = date.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%m-%d-%Y") {
+        return Ok(date);
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match input.as_str() {
+        "today" => return Ok(today),
+        "yesterday" => return today.checked_sub_days(Days::new(1)).ok_or_else(err),
+        _ => {}
+    }
+
+    if let Some(days) = input
+        .strip_suffix("days ago")
+        .or_else(|| input.strip_suffix("day ago"))
+    {
+        let days = days.trim().parse::<u64>().map_err(|_| err())?;
+        return today.checked_sub_days(Days::new(days)).ok_or_else(err);
+    }
+
+    let weekday = input
+        .strip_prefix("last ")
+        .unwrap_or(&input)
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| err())?;
+
+    let days_back =
+        match (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7 {
+            0 => 7,
+            days => days,
+        };
+
+    today
+        .checked_sub_days(Days::new(days_back.into()))
+        .ok_or_else(err)
+}
+
+/// Sets the format dates are displayed in for this run, taking precedence over the
+/// `display_date_format` of the config.
+///
+/// ## Errors
+///
+/// * `Error::InvalidDateFormat` - If dates cannot be formatted with the format.
+pub fn set_display_format(format: &str) -> crate::error::Result<()> {
+    validate_format(format)?;
+    let _ = DISPLAY_FORMAT.set(Some(format.to_owned()));
+
+    Ok(())
+}
+
+/// Sets the format dates are displayed in for this run from the config, unless one
+/// was set already, so that an invalid one is reported before printing anything.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If the config file cannot be read.
+/// * `Error::CannotParseConfig` - If the config file is malformed or its display date format is invalid.
+pub fn load_display_format() -> crate::error::Result<()> {
+    if DISPLAY_FORMAT.get().is_none() {
+        let _ = DISPLAY_FORMAT.set(Config::load()?.display_date_format);
+    }
+
+    Ok(())
+}
+
+/// Returns how a date is displayed, in the display format when one is set and as
+/// `stored`, e.g. the name of its directory, otherwise.
+pub fn display(date: NaiveDate, stored: &str) -> String {
+    let format = DISPLAY_FORMAT.get_or_init(|| {
+        Config::load()
+            .ok()
+            .and_then(|config| config.display_date_format)
+    });
+
+    match format {
+        Some(format) => date.format(format).to_string(),
+        None => stored.to_owned(),
+    }
+}
+
+/// Returns how a date is displayed, in the display format when one is set and in
+/// the format date directories are named in otherwise.
+pub fn display_date(date: NaiveDate, config: &Config) -> String {
+    display(date, &config.date_format.format(date))
+}
+
+/// Checks that dates can be formatted with a chrono format string, which rules out
+/// unknown specifiers as well as times, e.g. "%H:%M".
+///
+/// ## Errors
+///
+/// * `Error::InvalidDateFormat` - If dates cannot be formatted with the format.
+pub fn validate_format(format: &str) -> crate::error::Result<()> {
+    let mut formatted = String::new();
+    write!(formatted, "{}", NaiveDate::default().format(format))
+        .map_err(|_| Error::InvalidDateFormat(format.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{parse_date_from, validate_format};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    // a Monday
+    const TODAY: (i32, u32, u32) = (2024, 10, 7);
+
+    fn parse(input: &str) -> Option<NaiveDate> {
+        let (year, month, day) = TODAY;
+        parse_date_from(input, date(year, month, day)).ok()
+    }
+
+    #[test]
+    fn parses_absolute_dates() {
+        assert_eq!(parse("10-5-2024"), Some(date(2024, 10, 5)));
+        assert_eq!(parse("10-05-2024"), Some(date(2024, 10, 5)));
+        assert_eq!(parse("2024-10-05"), Some(date(2024, 10, 5)));
+    }
+
+    #[test]
+    fn parses_relative_dates() {
+        assert_eq!(parse("today"), Some(date(2024, 10, 7)));
+        assert_eq!(parse("Yesterday"), Some(date(2024, 10, 6)));
+        assert_eq!(parse("3 days ago"), Some(date(2024, 10, 4)));
+        assert_eq!(parse("1 day ago"), Some(date(2024, 10, 6)));
+    }
+
+    #[test]
+    fn weekdays_refer_to_the_past() {
+        assert_eq!(parse("friday"), Some(date(2024, 10, 4)));
+        assert_eq!(parse("last sunday"), Some(date(2024, 10, 6)));
+        assert_eq!(parse("last monday"), Some(date(2024, 9, 30)));
+    }
+
+    #[test]
+    fn rejects_unknown_dates() {
+        assert_eq!(parse("13-1-2024"), None);
+        assert_eq!(parse("someday"), None);
+        assert_eq!(parse("x days ago"), None);
+    }
+
+    #[test]
+    fn display_formats_are_validated() {
+        assert!(validate_format("%b %-d, %Y").is_ok());
+        assert_eq!(
+            date(2024, 10, 5).format("%b %-d, %Y").to_string(),
+            "Oct 5, 2024"
+        );
+        assert!(validate_format("%Y-%m-%d").is_ok());
+        assert!(validate_format("%Q").is_err());
+        assert!(validate_format("%d %H:%M").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+
+use crate::{
+    config::Config,
+    crypto,
+    error::Error,
+    front_matter::FrontMatter,
+    notes::{self, Day},
+};
+
+/// Merges the directories referring to the same date, e.g. "1-5-2024" and
+/// "01-05-2024", into the one named in the configured format, returning a line
+/// per moved or merged file followed by a summary. With `dry_run`, nothing is
+/// changed.
+///
+/// Files missing from the kept directory are moved into it, and notes present in
+/// both are merged by appending the body of the redundant note and combining
+/// their front matter. A redundant directory is removed once emptied, and kept
+/// when it holds a file other than a note which the kept directory also has.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If the notes directory, a date directory or a note cannot be read.
+/// * `Error::CannotCreateDir` - If a directory cannot be created.
+/// * `Error::CannotWriteToFile` - If a file cannot be moved or a note written.
+/// * `Error::CannotRemoveFile` - If a redundant directory cannot be removed.
+pub fn dedupe(root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+    let config = Config::load()?;
+    let mut lines = Vec::new();
+    let mut deduped = 0;
+
+    for (date, paths) in duplicates(notes::days(root_dir)?) {
+        let target = root_dir.join(config.date_format.format(date));
+        let name = dir_name(&target);
+
+        for source in paths.iter().filter(|path| **path != target) {
+            let mut kept = Vec::new();
+            merge_dir(
+                root_dir,
+                source,
+                &target,
+                dry_run,
+                config.encrypt,
+                &mut lines,
+                &mut kept,
+            )?;
+
+            let verb = if dry_run { "would remove" } else { "removed" };
+            if kept.is_empty() {
+                if !dry_run {
+                    fs::remove_dir_all(source)
+                        .map_err(|err| Error::CannotRemoveFile(source.clone(), err))?;
+                }
+                lines.push(format!("{verb} {}", dir_name(source)));
+            } else {
+                lines.push(format!(
+                    "kept {}, as {name} already has {}",
+                    dir_name(source),
+                    kept.join(", ")
+                ));
+            }
+        }
+        deduped += 1;
+    }
+
+    let verb = if dry_run {
+        "would deduplicate"
+    } else {
+        "deduplicated"
+    };
+    lines.push(format!("{verb} {deduped} date(s)"));
+
+    Ok(lines)
+}
+
+/// Groups the date directories by date, keeping only the dates with several ones.
+fn duplicates(days: Vec<Day>) -> Vec<(NaiveDate, Vec<PathBuf>)> {
+    let mut groups: Vec<(NaiveDate, Vec<PathBuf>)> = Vec::new();
+
+    for day in days {
+        match groups.last_mut() {
+            Some((date, paths)) if *date == day.date => paths.push(day.path),
+            _ => groups.push((day.date, vec![day.path])),
+        }
+    }
+    groups.retain(|(_, paths)| paths.len() > 1);
+
+    groups
+}
+
+/// Moves every file of a directory into another, merging the notes both hold and
+/// collecting the names of the other files both hold into `kept`.
+fn merge_dir(
+    root_dir: &Path,
+    source: &Path,
+    target: &Path,
+    dry_run: bool,
+    encrypt: bool,
+    lines: &mut Vec<String>,
+    kept: &mut Vec<String>,
+) -> crate::error::Result<()> {
+    let mut entries = fs::read_dir(source)
+        .map_err(|err| Error::CannotReadFile(source.to_path_buf(), err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for path in entries {
+        let Some(file_name) = path.file_name() else {
+            continue;
+        };
+        let destination = target.join(file_name);
+        let from = relative(root_dir, &path);
+        let to = relative(root_dir, &destination);
+
+        if path.is_dir() && destination.is_dir() {
+            merge_dir(root_dir, &path, &destination, dry_run, encrypt, lines, kept)?;
+        } else if !destination.exists() {
+            if !dry_run {
+                fs::create_dir_all(target)
+                    .map_err(|err| Error::CannotCreateDir(target.display().to_string(), err))?;
+                fs::rename(&path, &destination)
+                    .map_err(|err| Error::CannotWriteToFile(path.clone(), err))?;
+            }
+            let verb = if dry_run { "would move" } else { "moved" };
+            lines.push(format!("{verb} {from} to {to}"));
+        } else if is_note(&path) && is_note(&destination) {
+            if !dry_run {
+                let raw = fs::read_to_string(&destination)
+                    .map_err(|err| Error::CannotReadFile(destination.clone(), err))?;
+                let sealed = encrypt || crypto::is_sealed(&raw);
+                let merged = merge_notes(&notes::read(&destination)?, &notes::read(&path)?);
+                let merged = if sealed {
+                    crypto::seal(&merged, root_dir)?
+                } else {
+                    merged
+                };
+
+                notes::write_atomically(&destination, &merged)?;
+                fs::remove_file(&path).map_err(|err| Error::CannotRemoveFile(path.clone(), err))?;
+            }
+            let verb = if dry_run { "would merge" } else { "merged" };
+            lines.push(format!("{verb} {from} into {to}"));
+        } else {
+            kept.push(relative(target, &destination));
+        }
+    }
+
+    Ok(())
+}
+
+fn is_note(path: &Path) -> bool {
+    path.is_file()
+        && path
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "txt")
+}
+
+/// Merges a note into another of the same name, appending its body.
+///
+/// The front matter of the kept note wins, gaining the tags and fields it lacks;
+/// the merged note was created when the earlier one was and updated when the later
+/// one was. A kept note without front matter takes the front matter of the other
+/// note, so that it doesn't end up within the body, and notes which both go
+/// without are simply concatenated.
+fn merge_notes(kept: &str, other: &str) -> String {
+    let kept_split = match FrontMatter::split(kept) {
+        Err(_) if !kept.starts_with("---\n") && other.starts_with("---\n") => {
+            let body = format!("\n{}", kept.trim_start_matches('\n'));
+            Ok((FrontMatter::default(), body))
+        }
+        split => split.map(|(meta, body)| (meta, body.to_owned())),
+    };
+    let (Ok((mut meta, body)), Ok((other_meta, other_body))) =
+        (kept_split, FrontMatter::split(other))
+    else {
+        return format!("{}\n{}", kept.trim_end(), other.trim_start_matches('\n'));
+    };
+
+    meta.add_tags(&other_meta.tags);
+    if meta.title.trim().is_empty() {
+        meta.title = other_meta.title;
+    }
+    meta.created = [meta.created.take(), other_meta.created]
+        .into_iter()
+        .flatten()
+        .min();
+    meta.updated = [meta.updated.take(), other_meta.updated]
+        .into_iter()
+        .flatten()
+        .max();
+    meta.pinned |= other_meta.pinned;
+    for (key, value) in other_meta.extra {
+        if !meta.extra.contains_key(&key) {
+            meta.extra.insert(key, value);
+        }
+    }
+
+    format!(
+        "{}{}\n{}",
+        meta.render(),
+        body.trim_end(),
+        other_body.trim_start_matches('\n')
+    )
+}
+
+fn relative(root_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(root_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use chrono::NaiveDate;
+
+    use super::{duplicates, merge_notes};
+    use crate::notes::Day;
+
+    #[test]
+    fn only_dates_with_several_directories_are_duplicates() {
+        let date = |day| NaiveDate::from_ymd_opt(2024, 1, day).unwrap();
+        let day = |day, name: &str| Day {
+            date: date(day),
+            path: PathBuf::from(name),
+        };
+
+        let days = vec![
+            day(5, "1-5-2024"),
+            day(5, "01-05-2024"),
+            day(6, "01-06-2024"),
+            day(7, "2024-01-07"),
+            day(7, "01-07-2024"),
+        ];
+
+        assert_eq!(
+            duplicates(days),
+            [
+                (date(5), vec!["1-5-2024".into(), "01-05-2024".into()]),
+                (date(7), vec!["2024-01-07".into(), "01-07-2024".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_notes_combine_their_front_matter() {
+        let kept = "---\ntitle: \"rust\"\ntags: [a]\ncreated: 2024-01-05T10:00:00+00:00\nupdated: 2024-01-05T10:00:00+00:00\n---\n\n- one\n";
+        let other = "---\ntitle: \"rust\"\ntags: [b, a]\ncreated: 2024-01-05T08:00:00+00:00\nupdated: 2024-01-05T12:00:00+00:00\nsource: book\n---\n\n- two\n";
+
+        assert_eq!(
+            merge_notes(kept, other),
+            "---\ntitle: \"rust\"\ntags: [a, b]\ncreated: 2024-01-05T08:00:00+00:00\nupdated: 2024-01-05T12:00:00+00:00\nsource: book\n---\n\n- one\n- two\n"
+        );
+        assert_eq!(merge_notes("- one\n", "\n- two\n"), "- one\n- two\n");
+        assert_eq!(
+            merge_notes("- one\n", "---\ntitle: \"rust\"\ntags: [b]\n---\n\n- two\n"),
+            "---\ntitle: \"rust\"\ntags: [b]\n---\n\n- one\n- two\n"
+        );
+    }
+}
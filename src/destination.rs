@@ -0,0 +1,135 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use clap::Args;
+
+use crate::{error::Error, notes};
+
+/// Options writing what a command prints to a file instead of stdout.
+#[derive(Args, Debug, Default)]
+#[group(skip)]
+pub struct Destination {
+    /// Write the output to this file instead of stdout, creating its parent directories
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Overwrite the output file when it already exists
+    #[clap(long, requires = "output")]
+    pub force: bool,
+}
+
+impl Destination {
+    /// Opens the output file, or stdout when there is none, and hands it to `write`,
+    /// flushing it once written. Colors are left out of files, and a closed pipe on
+    /// stdout, as with `til list | head`, is not an error.
+    ///
+    /// The output is written to a temporary file next to the output file, which
+    /// only replaces it once `write` succeeds, so that a failing command leaves no
+    /// output file behind and an existing one untouched.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::FileExists` - If the output file exists and `--force` isn't passed.
+    /// * `Error::CannotCreateDir` - If a parent directory of the output file cannot be created.
+    /// * `Error::CannotOpenOrCreatePath` - If the temporary file cannot be created.
+    /// * `Error::CannotWriteToFile` - If the output cannot be flushed or the output file replaced.
+    /// * Any error returned by `write`.
+    pub fn with(
+        &self,
+        write: impl FnOnce(&mut Box<dyn Write>) -> crate::error::Result<()>,
+    ) -> crate::error::Result<()> {
+        let Some(path) = &self.output else {
+            let mut out: Box<dyn Write> = Box::new(BufWriter::new(io::stdout().lock()));
+            write(&mut out)?;
+            return match out.flush() {
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                flushed => flushed.map_err(|err| Error::CannotWriteToFile("stdout".into(), err)),
+            };
+        };
+
+        if path.exists() && !self.force {
+            return Err(Error::FileExists(path.clone()));
+        }
+        if let Some(parent) = path.parent().filter(|parent| !parent.exists()) {
+            fs::create_dir_all(parent)
+                .map_err(|err| Error::CannotCreateDir(parent.display().to_string(), err))?;
+        }
+
+        let temporary = notes::temporary_path(path);
+        let file = File::create(&temporary)
+            .map_err(|err| Error::CannotOpenOrCreatePath(temporary.clone(), err))?;
+        owo_colors::set_override(false);
+
+        let mut out: Box<dyn Write> = Box::new(BufWriter::new(file));
+        let written = write(&mut out).and_then(|_| {
+            out.flush()
+                .map_err(|err| Error::CannotWriteToFile(path.clone(), err))
+        });
+        drop(out);
+
+        written
+            .and_then(|_| {
+                fs::rename(&temporary, path)
+                    .map_err(|err| Error::CannotWriteToFile(path.clone(), err))
+            })
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&temporary);
+            })
+    }
+
+    /// Writes lines to the output file or stdout.
+    ///
+    /// ## Errors
+    ///
+    /// The same as [`Destination::with`].
+    pub fn write_lines(&self, lines: &[String]) -> crate::error::Result<()> {
+        let name = self.output.clone().unwrap_or_else(|| "stdout".into());
+
+        self.with(|out| {
+            lines
+                .iter()
+                .try_for_each(|line| writeln!(out, "{line}"))
+                .map_err(|err| Error::CannotWriteToFile(name, err))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::Destination;
+    use crate::error::Error;
+
+    #[test]
+    fn output_files_are_only_overwritten_with_force() {
+        let dir = env::temp_dir().join(format!("til-destination-{}", std::process::id()));
+        let path = dir.join("reports").join("today.md");
+        let mut destination = Destination {
+            output: Some(path.clone()),
+            force: false,
+        };
+
+        destination.write_lines(&["- one".to_owned()]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- one\n");
+
+        let err = destination.write_lines(&["- two".to_owned()]).unwrap_err();
+        assert!(matches!(err, Error::FileExists(_)));
+
+        destination.force = true;
+        destination.write_lines(&["- two".to_owned()]).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- two\n");
+
+        let err = destination
+            .with(|_| Err(Error::NoMatchesFound("three".to_owned())))
+            .unwrap_err();
+        assert!(matches!(err, Error::NoMatchesFound(_)));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "- two\n");
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 1);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,123 @@
+use std::{collections::HashSet, path::Path};
+
+use clap::Args;
+
+use crate::{
+    date::parse_date,
+    error::Error,
+    notes::{self, Day, Note},
+    style,
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Diff {
+    /// The date to compare from ("MM-DD-YYYY", "yesterday", ...)
+    pub a: String,
+
+    /// The date to compare with ("MM-DD-YYYY", "today", ...)
+    pub b: String,
+}
+
+impl Diff {
+    /// Compares the bullets of every note of two dates, returning the ones only the
+    /// first date has as `- bullet` lines in red, followed by the ones only the
+    /// second date has as `+ bullet` lines in green. Bullets are compared ignoring
+    /// surrounding whitespace, regardless of the note holding them.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If a date cannot be parsed.
+    /// * `Error::NoNotesFound` - If a date has no directory.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    pub fn lines(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let (a, b) = (day(root_dir, &self.a)?, day(root_dir, &self.b)?);
+        let (a_bullets, b_bullets) = (bullets(&a)?, bullets(&b)?);
+        let (removed, added) = difference(&a_bullets, &b_bullets);
+
+        if removed.is_empty() && added.is_empty() {
+            return Ok(vec![format!(
+                "{} and {} have the same bullets",
+                a.name(),
+                b.name()
+            )]);
+        }
+
+        let mut lines = vec![
+            style::heading(&format!("--- {}", a.name())),
+            style::heading(&format!("+++ {}", b.name())),
+        ];
+        lines.extend(
+            removed
+                .iter()
+                .map(|bullet| style::removed(&format!("- {bullet}"))),
+        );
+        lines.extend(
+            added
+                .iter()
+                .map(|bullet| style::added(&format!("+ {bullet}"))),
+        );
+
+        Ok(lines)
+    }
+}
+
+fn day(root_dir: &Path, date: &str) -> crate::error::Result<Day> {
+    let date = parse_date(date)?;
+    let day = Day {
+        date,
+        path: notes::day_path(root_dir, date)?,
+    };
+    if !day.path.exists() {
+        return Err(Error::NoNotesFound(day.name()));
+    }
+
+    Ok(day)
+}
+
+/// Reads the bullets of every note of a date, in the order of the notes.
+fn bullets(day: &Day) -> crate::error::Result<Vec<String>> {
+    let mut bullets = Vec::new();
+    for path in notes::notes_in(&day.path)? {
+        let contents = notes::read(&path)?;
+        bullets.extend(Note::parse(day.date, &path, &contents).bullets);
+    }
+
+    Ok(bullets)
+}
+
+/// Returns the bullets only `a` has and the ones only `b` has, each once and in
+/// their order, ignoring surrounding whitespace.
+fn difference<'a>(a: &'a [String], b: &'a [String]) -> (Vec<&'a str>, Vec<&'a str>) {
+    let only = |bullets: &'a [String], other: &'a [String]| {
+        let other = other
+            .iter()
+            .map(|bullet| bullet.trim())
+            .collect::<HashSet<_>>();
+        let mut seen = HashSet::new();
+
+        bullets
+            .iter()
+            .map(|bullet| bullet.trim())
+            .filter(|bullet| !other.contains(bullet) && seen.insert(*bullet))
+            .collect::<Vec<_>>()
+    };
+
+    (only(a, b), only(b, a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::difference;
+
+    #[test]
+    fn bullets_only_one_date_has_differ() {
+        let monday = ["standup at 10", "fixed the flaky test", "review PR 12"].map(str::to_owned);
+        let tuesday = ["standup at 10 ", "review PR 12", "ship it", "ship it"].map(str::to_owned);
+
+        let (removed, added) = difference(&monday, &tuesday);
+        assert_eq!(removed, ["fixed the flaky test"]);
+        assert_eq!(added, ["ship it"]);
+        assert_eq!(difference(&monday, &monday), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::{
+    front_matter::FrontMatter,
+    notes::{self, Extension},
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Doctor {
+    /// Rewrite the front matter of problem notes into canonical form
+    #[clap(long)]
+    pub fix: bool,
+}
+
+/// What's wrong with a note, along with its repaired contents when it can be fixed.
+#[derive(Debug, PartialEq)]
+struct Diagnosis {
+    issues: Vec<&'static str>,
+    repaired: Option<String>,
+}
+
+impl Doctor {
+    /// Checks the front matter of every markdown note, returning a line per problem
+    /// note listing its issues, followed by a summary. With `--fix`, every note which
+    /// can be repaired is rewritten. Plain text notes have no front matter to check.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::CannotWriteToFile` - If a repaired note cannot be written.
+    pub fn check(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let mut lines = Vec::new();
+
+        for day in notes::days(root_dir)? {
+            for path in notes::notes_in(&day.path)?
+                .into_iter()
+                .filter(|path| Extension::of(path) == Some(Extension::Md))
+            {
+                let (contents, sealed) = notes::read_sealed(&path)?;
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let diagnosis = diagnose(&contents, &stem);
+
+                if diagnosis.issues.is_empty() {
+                    continue;
+                }
+
+                let name = day.note_name(&path);
+                let status = match (&diagnosis.repaired, self.fix) {
+                    (Some(repaired), true) => {
+                        notes::write_note(root_dir, &path, repaired, sealed)?;
+                        " (fixed)"
+                    }
+                    (None, _) => " (cannot be fixed automatically)",
+                    _ => "",
+                };
+
+                lines.push(format!("{name}: {}{status}", diagnosis.issues.join(", ")));
+            }
+        }
+
+        let summary = match lines.len() {
+            0 => "every note is healthy".to_owned(),
+            count if self.fix => format!("{count} note(s) with problems"),
+            count => format!("{count} note(s) with problems, pass --fix to repair them"),
+        };
+        lines.push(summary);
+
+        Ok(lines)
+    }
+}
+
+/// Looks for problems in the front matter of a note and how to repair them.
+///
+/// Front matter written indented by older versions is dedented, a missing title is
+/// taken from the file name, repeated tags are dropped, and notes without front
+/// matter get one. Front matter which isn't valid YAML cannot be repaired.
+fn diagnose(contents: &str, stem: &str) -> Diagnosis {
+    let mut issues = Vec::new();
+
+    let Some(rest) = contents.strip_prefix("---\n") else {
+        let meta = FrontMatter {
+            title: stem.to_owned(),
+            ..Default::default()
+        };
+
+        return Diagnosis {
+            issues: vec!["missing front matter"],
+            repaired: Some(format!("{}\n{}", meta.render(), contents)),
+        };
+    };
+
+    let lines: Vec<&str> = rest.split_inclusive('\n').collect();
+    let Some(end) = lines.iter().position(|line| line.trim() == "---") else {
+        return Diagnosis {
+            issues: vec!["unterminated front matter"],
+            repaired: None,
+        };
+    };
+
+    let is_indented = lines[..=end]
+        .iter()
+        .any(|line| line.starts_with([' ', '\t']) && !line.trim().is_empty());
+    let yaml: String = lines[..end]
+        .iter()
+        .map(|line| if is_indented { line.trim_start() } else { line })
+        .collect();
+
+    let mut body: Vec<&str> = lines[end + 1..].to_vec();
+    if is_indented {
+        issues.push("indented front matter");
+
+        // The blank line closing indented front matter was left indented too, which
+        // also indented the first bullet written after it.
+        for line in body.iter_mut() {
+            let is_blank = line.trim().is_empty();
+            *line = line.trim_start_matches([' ', '\t']);
+            if !is_blank {
+                break;
+            }
+        }
+    }
+
+    let wrapped = format!("---\n{yaml}---\n");
+    let Ok(mut meta) = FrontMatter::parse(&wrapped) else {
+        issues.push("front matter isn't valid YAML");
+        return Diagnosis {
+            issues,
+            repaired: None,
+        };
+    };
+
+    if meta.title.is_empty() {
+        issues.push("missing title");
+        meta.title = stem.to_owned();
+    }
+
+    let tags = std::mem::take(&mut meta.tags);
+    meta.add_tags(&tags);
+    if meta.tags.len() != tags.len() {
+        issues.push("repeated tags");
+    }
+
+    let repaired = if issues.is_empty() {
+        None
+    } else {
+        Some(format!("{}{}", meta.render(), body.concat()))
+    };
+
+    Diagnosis { issues, repaired }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::diagnose;
+
+    #[test]
+    fn indented_front_matter_is_dedented() {
+        let contents = "---\n    title: \"default\"\n    tags: [a, b, a]\n    ---\n    \n    - first\n- second\n";
+        let diagnosis = diagnose(contents, "default");
+
+        assert_eq!(diagnosis.issues, ["indented front matter", "repeated tags"]);
+        assert_eq!(
+            diagnosis.repaired.unwrap(),
+            "---\ntitle: \"default\"\ntags: [a, b]\n---\n\n- first\n- second\n"
+        );
+    }
+
+    #[test]
+    fn healthy_and_broken_notes() {
+        let healthy = "---\ntitle: \"rust\"\ntags: [a]\n---\n\n- note\n";
+        assert!(diagnose(healthy, "rust").issues.is_empty());
+
+        let missing = diagnose("- note\n", "rust");
+        assert_eq!(
+            missing.repaired.unwrap(),
+            "---\ntitle: \"rust\"\ntags: []\n---\n\n- note\n"
+        );
+
+        let invalid = diagnose("---\ntitle: [\n---\n", "rust");
+        assert_eq!(invalid.issues, ["front matter isn't valid YAML"]);
+        assert!(invalid.repaired.is_none());
+    }
+}
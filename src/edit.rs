@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{config::Config, date::parse_date, editor, error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Edit {
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+}
+
+impl Edit {
+    /// Opens an existing note in `$EDITOR`.
+    ///
+    /// An encrypted note is decrypted into a private temporary file for the editor,
+    /// then encrypted again once edited. Once the editor exits, a warning is printed
+    /// if the note no longer starts with its front matter block.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotFindFile` - If the note does not exist.
+    /// * `Error::CannotReadFile` - If the note cannot be read once edited.
+    /// * `Error::CannotDecrypt` - If the note is encrypted and cannot be decrypted.
+    /// * `Error::CannotWriteToFile` - If an encrypted note cannot be written back.
+    /// * Any error returned by `editor::open`.
+    pub fn open(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let config = Config::load()?;
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        let path = notes::note_path(root_dir, date, title)?;
+
+        if !path.exists() {
+            return Err(Error::CannotFindFile(path));
+        }
+
+        let (contents, sealed) = notes::read_sealed(&path)?;
+        let contents = if sealed {
+            let edited = editor::revise(&contents)?;
+            if edited != contents {
+                notes::write_note(root_dir, &path, &edited, true)?;
+            }
+            edited
+        } else {
+            editor::open(&path)?;
+            notes::read(&path)?
+        };
+
+        if !contents.starts_with("---\n") {
+            eprintln!(
+                "warning: the front matter of {} was removed, tags can no longer be updated",
+                path.display()
+            );
+        }
+
+        Ok(())
+    }
+}
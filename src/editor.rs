@@ -0,0 +1,78 @@
+use std::{env, fs, path::Path, process};
+
+use crate::{config::Config, error::Error};
+
+#[cfg(windows)]
+const DEFAULT_EDITOR: &str = "notepad";
+#[cfg(not(windows))]
+const DEFAULT_EDITOR: &str = "vi";
+
+/// Opens a file in the user's `$EDITOR`, waiting for it to exit.
+///
+/// The editor set in the config takes precedence over `$EDITOR`, and `vi` (or
+/// `notepad` on Windows) is used when neither is set.
+///
+/// ## Errors
+///
+/// * `Error::CannotLaunchEditor` - If the editor cannot be started.
+/// * `Error::EditorFailed` - If the editor exits unsuccessfully.
+pub fn open(path: &Path) -> crate::error::Result<()> {
+    let editor = Config::load()?
+        .editor
+        .or_else(|| env::var("EDITOR").ok())
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_owned());
+
+    let mut parts = editor.split_whitespace();
+    let program = parts
+        .next()
+        .ok_or(Error::CannotLaunchEditor(editor.clone()))?;
+
+    let status = process::Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|_| Error::CannotLaunchEditor(editor.clone()))?;
+
+    if !status.success() {
+        return Err(Error::EditorFailed(editor));
+    }
+
+    Ok(())
+}
+
+/// Composes a note in the user's `$EDITOR` and returns what was written.
+///
+/// ## Errors
+///
+/// The same as [`revise`].
+pub fn compose() -> crate::error::Result<String> {
+    revise("")
+}
+
+/// Opens contents in the user's `$EDITOR` and returns them as edited, e.g. to edit
+/// an encrypted note without decrypting it in place.
+///
+/// The contents are written to a temporary file with a random name which only the
+/// user can read, and which is removed once the editor exits, whether it succeeded
+/// or not.
+///
+/// ## Errors
+///
+/// * `Error::CannotWriteToFile` - If the temporary file cannot be created or written.
+/// * `Error::CannotReadFile` - If the temporary file cannot be read back.
+/// * Any error returned by [`open`].
+pub fn revise(contents: &str) -> crate::error::Result<String> {
+    let file = tempfile::Builder::new()
+        .prefix("til-")
+        .suffix(".md")
+        .tempfile()
+        .map_err(|err| Error::CannotWriteToFile(env::temp_dir(), err))?;
+    fs::write(file.path(), contents)
+        .map_err(|err| Error::CannotWriteToFile(file.path().to_path_buf(), err))?;
+    // The file is closed before the editor opens it, and removed when dropped.
+    let path = file.into_temp_path();
+
+    open(&path)?;
+    fs::read_to_string(&path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))
+}
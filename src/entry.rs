@@ -1,58 +1,428 @@
 use std::{
-    fs::{self, OpenOptions},
-    io::Write,
+    fs,
+    io::{self, IsTerminal, Read},
     path::{Path, PathBuf},
+    sync::LazyLock,
 };
 
-use crate::{find_root_dir, Error};
-use chrono::{Datelike, Local};
+use crate::{
+    config::Config,
+    date::parse_date,
+    editor,
+    front_matter::{self, FrontMatter},
+    git,
+    index::Index,
+    links::parse_wikilinks,
+    notes::{self, Extension, Note},
+    style,
+    template::{self, Placeholders},
+    Error,
+};
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat};
 use clap::Args;
 use regex::Regex;
+use serde_yaml::{Mapping, Value};
 
 #[derive(Args, Debug)]
 pub struct Entry {
-    content: String,
+    /// The content of the note, one bullet per value, read from stdin when omitted
+    content: Vec<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    title: Option<String>,
 
-    #[clap(long, use_value_delimiter = true, default_value = "")]
+    /// Specify the file name of the note, without extension, defaults to the title
+    #[clap(long)]
+    file: Option<String>,
+
+    /// Tags of the note, separated by commas unless configured otherwise
+    #[clap(long)]
     tags: Vec<String>,
+
+    /// Specify the date the note belongs to ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    date: Option<String>,
+
+    /// Compose the note in $EDITOR
+    #[clap(short, long, conflicts_with = "content")]
+    edit: bool,
+
+    /// Prefix each bullet with the current time, e.g. "- [14:32] note"
+    #[clap(long)]
+    timestamp: bool,
+
+    /// Start bullets with this instead of the configured prefix, e.g. "* "
+    #[clap(long, value_name = "PREFIX")]
+    bullet_prefix: Option<String>,
+
+    /// Skip bullets already present in the note
+    #[clap(long)]
+    no_dup: bool,
+
+    /// Remove inline #hashtags from the note once added to its tags
+    #[clap(long)]
+    strip_tags: bool,
+
+    /// File the note under a topic directory within the date, e.g. "rust/async"
+    #[clap(long)]
+    topic: Option<String>,
+
+    /// Write the note to a file with this extension, only markdown notes get front matter
+    #[clap(long, value_enum, default_value_t)]
+    ext: Extension,
+
+    /// Write only the bullets, without generating or updating the front matter
+    #[clap(long = "no-frontmatter")]
+    no_front_matter: bool,
+
+    /// Use the first "# " heading of the content as the title, removing it from the note
+    #[clap(long, conflicts_with = "title")]
+    title_from_h1: bool,
+
+    /// Render the front matter of a new note from this file instead of the configured template
+    #[clap(long)]
+    template: Option<PathBuf>,
+
+    /// Record where the note was learned from, e.g. the URL of an article, in its front matter
+    #[clap(long)]
+    source: Option<String>,
+
+    /// Also link to the source at the end of every bullet
+    #[clap(long, requires = "source")]
+    link_source: bool,
+
+    /// Mark the note as important, listing it with `til pinned`
+    #[clap(long)]
+    pin: bool,
+
+    /// Unmark a note marked with `--pin`
+    #[clap(long, conflicts_with = "pin")]
+    unpin: bool,
 }
 
 impl Entry {
-    pub fn write(&self) -> crate::error::Result<()> {
-        let path = self.build_path().map_err(|_| Error::CannotBuildPath)?;
+    /// Appends the bullets to the note, creating it along with its front matter when
+    /// it doesn't exist yet.
+    ///
+    /// The new contents of the note are written to a temporary file which then
+    /// replaces the note, so an interrupted write never leaves a partial note behind.
+    ///
+    /// A confirmation such as `✓ added to 08-18-2024/default.md` is printed once the
+    /// note is written, unless `quiet` is set. With a `verbose` level above zero, the
+    /// full path of the note is printed instead along with whether it was created or
+    /// appended to, and any `[[title]]` links the bullets make to other notes. When
+    /// enabled in the config, the notes directory is then committed to git.
+    ///
+    /// With `dry_run`, the path of the note and exactly what would be written to it
+    /// are printed instead, without touching the filesystem.
+    pub fn write(
+        &self,
+        root_dir: &Path,
+        verbose: u8,
+        quiet: bool,
+        dry_run: bool,
+    ) -> crate::error::Result<()> {
+        let config = Config::load()?;
+        if let Some(prefix) = &self.bullet_prefix {
+            notes::set_bullet_prefix(prefix)?;
+        }
+        let mut bullets = self.bullets()?;
+        let heading = if self.title_from_h1 {
+            take_heading(&mut bullets)
+        } else {
+            None
+        };
+
+        let title = self
+            .title
+            .clone()
+            .or(heading)
+            .or_else(|| self.file.clone())
+            .unwrap_or_else(|| config.default_title().to_owned());
+        let file_name = self.file.as_deref().unwrap_or(&title);
+
+        let tags = self.merged_tags(&bullets, config.tag_delimiter());
+        let tags = if config.case_insensitive_tags {
+            front_matter::lowercase_tags(&tags)
+        } else {
+            tags
+        };
+        if self.strip_tags {
+            bullets = bullets
+                .iter()
+                .map(|bullet| strip_inline_tags(bullet))
+                .filter(|bullet| !bullet.is_empty())
+                .collect();
+        }
+        let date = self.date()?;
+        let path = if dry_run {
+            self.note_path(root_dir, date, file_name)
+        } else {
+            self.build_path(root_dir, date, file_name)
+        }
+        .map_err(|err| match err {
+            Error::InvalidTitle(_) | Error::InvalidTopic(_) => err,
+            _ => Error::CannotBuildPath,
+        })?;
 
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&path)
-            .map_err(|_| Error::CannotOpenOrCreatePath(path.clone()))?;
+        if self.no_dup && path.exists() {
+            let mut existing = Note::from_file(&path)?.bullets;
 
-        let file_size = file
-            .metadata()
-            .map_err(|_| Error::CannotReadFile(path.clone()))?
-            .len();
+            bullets.retain(|bullet| {
+                if is_duplicate(&existing, bullet) {
+                    eprintln!("skipped duplicate bullet: {}", bullet.trim());
+                    false
+                } else {
+                    existing.push(bullet.to_owned());
+                    true
+                }
+            });
 
-        if file_size == 0 {
-            file.write_all(self.generate_meta().as_bytes())
-                .map_err(|_| Error::CannotWriteToFile(path.clone()))?;
-        } else if !self.tags.is_empty() {
-            self.update_meta(&path)?;
+            if bullets.is_empty() {
+                return Ok(());
+            }
         }
 
-        file.write_all(format!("- {}\n", self.content).as_bytes())
-            .map_err(|_| Error::CannotWriteToFile(path.clone()))
-    }
+        let now = Local::now();
+        let prefix = if self.timestamp || config.timestamp {
+            now.format("[%H:%M] ").to_string()
+        } else {
+            String::new()
+        };
+        let source = self
+            .source
+            .as_deref()
+            .map(str::trim)
+            .filter(|source| !source.is_empty());
+        let suffix = match source.filter(|_| self.link_source) {
+            Some(source) => format!(" {}", source_link(source)),
+            None => String::new(),
+        };
+        let bullets = bullets
+            .iter()
+            .map(|bullet| format!("{prefix}{bullet}{suffix}"))
+            .collect::<Vec<_>>();
+        let lines = notes::bullet_lines(&bullets);
+        let has_front_matter = self.ext == Extension::Md && !self.no_front_matter;
+        if !has_front_matter && (!tags.is_empty() || source.is_some() || self.pin) {
+            warn_without_front_matter(&path);
+        }
+        let mut note = new_note(date, &title, &tags, bullets, &iso_timestamp(now));
+        let is_new = fs::metadata(&path).map_or(true, |metadata| metadata.len() == 0);
+        if let Some(template) = self
+            .template
+            .clone()
+            .or_else(|| config.template())
+            .filter(|_| has_front_matter && is_new)
+        {
+            let values = Placeholders {
+                date,
+                title: &title,
+                tags: &tags,
+                now: &iso_timestamp(now),
+            };
+            let meta = template::render_file(&template, &values)?;
+            note = Note {
+                title: meta.title,
+                tags: meta.tags,
+                created: meta.created,
+                updated: meta.updated,
+                extra: meta.extra,
+                ..note
+            };
+        }
+        if let Some(source) = source {
+            add_source(&mut note.extra, source);
+        }
+        note.pinned |= self.pin;
+
+        if dry_run {
+            if is_new {
+                println!("would create {}:", path.display());
+            } else {
+                if let Some((meta, _)) = has_front_matter
+                    .then(|| self.updated_meta(&path, &title, &tags, &config, now))
+                    .transpose()?
+                    .flatten()
+                {
+                    println!("would update the front matter of {}:", path.display());
+                    print!("{}", meta.render());
+                }
+                println!("would append to {}:", path.display());
+            }
 
-    fn build_path(&self) -> crate::error::Result<PathBuf> {
-        let time = Local::now();
-        let date = format!("{:02}-{:02}-{}", time.month(), time.day(), time.year());
+            if is_new && has_front_matter {
+                print!("{}", note.to_markdown());
+            } else {
+                print!("{lines}");
+            }
 
-        let root_dir = find_root_dir().ok_or(Error::CannotFindDir("root".to_owned()))?;
-        let path = {
-            let mut path = Path::new(&root_dir).join(&date).join("default");
-            path.set_extension("md");
-            path
+            return Ok(());
+        }
+
+        let (existing, is_sealed) = if path.exists() {
+            notes::read_sealed(&path)?
+        } else {
+            (String::new(), false)
         };
+        let is_new = existing.is_empty();
+
+        let updated = (has_front_matter && !is_new)
+            .then(|| self.updated_meta(&path, &title, &tags, &config, now))
+            .transpose()?
+            .flatten();
+        let contents = written(&note, &existing, updated, &lines, has_front_matter);
+        notes::write_note(root_dir, &path, &contents, config.encrypt || is_sealed)?;
+        let _ = Index::record(root_dir, &path);
+
+        if verbose > 0 {
+            let action = if is_new { "created" } else { "appended to" };
+            let path = fs::canonicalize(&path).unwrap_or(path);
+            println!("{} {}", action, path.display());
+            parse_wikilinks(&lines)
+                .iter()
+                .for_each(|link| println!("linked to \"{link}\""));
+        } else if !quiet {
+            let action = if is_new { "created" } else { "added to" };
+            let name = path.strip_prefix(root_dir).unwrap_or(&path);
+            println!("{} {action} {}", style::success("✓"), name.display());
+        }
+
+        if config.git {
+            let message = format!("til: {}", config.date_format.format(date));
+            git::commit(root_dir, &message, verbose)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the bullets to add to the note.
+    ///
+    /// Every non-blank value passed as an argument becomes its own bullet. When
+    /// composing in `$EDITOR`, or when no content is passed as an argument and stdin
+    /// is piped, every non-empty line becomes its own bullet. Empty stdin is
+    /// accepted, e.g. to create a note before writing to it.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::EmptyEditorContent` - If the note composed in `$EDITOR` is empty.
+    /// * `Error::EmptyContent` - If every value passed as an argument is empty or whitespace.
+    /// * `Error::NoContent` - If no content is passed and stdin is a terminal.
+    /// * `Error::CannotReadStdin` - If stdin cannot be read.
+    fn bullets(&self) -> crate::error::Result<Vec<String>> {
+        if self.edit {
+            let bullets = lines_to_bullets(&editor::compose()?);
+            if bullets.is_empty() {
+                return Err(Error::EmptyEditorContent);
+            }
+
+            return Ok(bullets);
+        }
+
+        let bullets = self
+            .content
+            .iter()
+            .filter(|content| !content.trim().is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        if !bullets.is_empty() {
+            return Ok(bullets);
+        }
+        if !self.content.is_empty() {
+            return Err(Error::EmptyContent);
+        }
+
+        let mut stdin = io::stdin();
+        if stdin.is_terminal() {
+            return Err(Error::NoContent);
+        }
+
+        let mut input = String::new();
+        stdin
+            .read_to_string(&mut input)
+            .map_err(|_| Error::CannotReadStdin)?;
+
+        Ok(lines_to_bullets(&input))
+    }
+
+    /// Returns the tags of the note split on a delimiter, with surrounding whitespace
+    /// trimmed and inner whitespace collapsed, leaving out any empty or repeated tag.
+    fn tags(&self, delimiter: char) -> Vec<String> {
+        let mut tags = Vec::new();
+
+        for tag in self.tags.iter().flat_map(|tags| tags.split(delimiter)) {
+            let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !tag.is_empty() && !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        tags
+    }
+
+    /// Returns the tags passed with `--tags` followed by any inline #hashtag found in
+    /// the bullets, without duplicates.
+    fn merged_tags(&self, bullets: &[String], delimiter: char) -> Vec<String> {
+        let mut tags = self.tags(delimiter);
+        for tag in bullets.iter().flat_map(|bullet| parse_inline_tags(bullet)) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+
+        tags
+    }
+
+    /// Returns the date the note belongs to, defaulting to today.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    fn date(&self) -> crate::error::Result<NaiveDate> {
+        match &self.date {
+            Some(date) => parse_date(date),
+            None => Ok(Local::now().date_naive()),
+        }
+    }
+
+    /// Returns the path of the note named `file_name` within the directory of the
+    /// date, or within its topic directory when a topic is given. The note doesn't
+    /// need to exist.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTitle` - If the name cannot be used as a file name.
+    /// * `Error::InvalidTopic` - If the topic cannot be used as a directory.
+    fn note_path(
+        &self,
+        root_dir: &Path,
+        date: NaiveDate,
+        file_name: &str,
+    ) -> crate::error::Result<PathBuf> {
+        let mut directory = notes::day_path(root_dir, date)?;
+        if let Some(topic) = &self.topic {
+            directory = notes::topic_dir(&directory, topic)?;
+        }
+
+        notes::note_with_extension(&directory, file_name, self.ext)
+    }
+
+    /// Returns the path of the note named `file_name` within the directory of the
+    /// date, creating the directory when needed. The name is sanitized like a title.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTitle` - If the name cannot be used as a file name.
+    /// * `Error::InvalidTopic` - If the topic cannot be used as a directory.
+    /// * `Error::CannotCreateDir` - If the date directory cannot be created.
+    fn build_path(
+        &self,
+        root_dir: &Path,
+        date: NaiveDate,
+        file_name: &str,
+    ) -> crate::error::Result<PathBuf> {
+        let path = self.note_path(root_dir, date, file_name)?;
 
         let directory = path
             .parent()
@@ -60,104 +430,576 @@ impl Entry {
 
         if !directory.exists() {
             fs::create_dir_all(directory)
-                .map_err(|_| Error::CannotCreateDir(path.display().to_string()))?;
+                .map_err(|err| Error::CannotCreateDir(path.display().to_string(), err))?;
         }
 
         Ok(path)
     }
 
-    /// Generates a metadata block for a note entry.
-    ///
-    /// This function will create a front matter block which includes the
-    /// title and tags of the note.
-    ///
-    /// ## Returns
-    ///
-    /// Returns a `String` containing the formatted metadata block.
-    ///
-    /// ## Examples
-    ///
-    /// ```
-    /// let entry = Entry {
-    ///     title: "Example Title".to_string(),
-    ///     tags: vec!["tag1".to_string(), "tag2".to_string()],
-    /// };
-    /// let meta = entry.generate_meta();
-    /// assert_eq!(meta, r#"---
-    /// title: "Example Title"
-    /// tags: [tag1, tag2]
-    /// ---
-    /// "#);
-    /// ```
-    fn generate_meta(&self) -> String {
-        format!(
-            r#"---
-    title: "default"
-    tags: [{}]
-    ---
-    
-    "#,
-            self.tags.join(", ")
-        )
-    }
-
     /// Updates the metadata block for a note entry.
     ///
-    /// This function reads the contents of a note entry, parses the metadata,
-    /// and updates the "tags" field with any new tags provided in the `Entry`. Tags
-    /// already present are not duplicated. The function assumes the metadata is at the
+    /// Reads the metadata block of a note entry and updates the "tags" field with
+    /// any of the given tags it lacks, returning it along with the body of the note.
+    ///
+    /// Tags already present are not duplicated, and any other field is kept as is.
+    /// The "title" field is never replaced, only filled in with the given title when
+    /// missing or empty. The "pinned" field is set with `--pin` and cleared with
+    /// `--unpin`.
+    /// The "updated" field is set to the given time, and a missing "created" field is
+    /// filled in from the file's metadata. The function assumes the metadata is at the
     /// beginning of the file, separated from the content by a `---` delimiter. If the
-    /// metadata is missing or cannot be parsed, an error is returned.
+    /// metadata is missing, `None` is returned, warning that the tags are left out
+    /// when tags were provided. If it cannot be parsed, an error is returned when tags
+    /// were provided, and `None` is returned otherwise.
     ///
     /// ## Arguments
     ///
-    /// * `path` - A reference to the path of the file where the metadata should be updated.
-    ///
-    /// ## Returns
-    ///
-    /// Returns a `Result` indicating success (`Ok(())`) or failure (`Error`).
+    /// * `path` - A reference to the path of the file whose metadata should be updated.
+    /// * `title` - The title to fill in when the metadata lacks one.
+    /// * `tags` - The tags to add to the metadata.
+    /// * `config` - The config, whose `case_insensitive_tags` lowercases the existing tags.
+    /// * `now` - The time the note is being appended to.
     ///
     /// ## Errors
     ///
-    /// * `Error::CannotOpenOrCreatePath` - If the file cannot be opened.
     /// * `Error::CannotReadFile` - If the file cannot be read.
     /// * `Error::CannotParseMetaData` - If the metadata cannot be parsed.
-    /// * `Error::CannotWriteToFile` - If the updated contents cannot be written back to the file.
-    fn update_meta(&self, path: &PathBuf) -> crate::error::Result<()> {
-        let mut contents =
-            fs::read_to_string(&path).map_err(|_| Error::CannotReadFile(path.clone()))?;
-
-        let meta = contents
-            .split("\n---\n")
-            .next()
-            .ok_or(Error::CannotParseMetaData)?;
-
-        let tags_regex =
-            Regex::new(r"(?m)^tags:\s*\[(.*?)\]$").map_err(|_| Error::CannotParseMetaData)?;
-        let mut new_tags = self.tags.clone();
-
-        if let Some(captures) = tags_regex.captures(meta) {
-            let existing_tags: Vec<String> = captures[1]
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .collect();
+    fn updated_meta(
+        &self,
+        path: &PathBuf,
+        title: &str,
+        tags: &[String],
+        config: &Config,
+        now: DateTime<Local>,
+    ) -> crate::error::Result<Option<(FrontMatter, String)>> {
+        let contents = notes::read(path)?;
 
-            new_tags.retain(|tag| !existing_tags.contains(tag));
+        let (mut meta, body) = match FrontMatter::split(&contents) {
+            Ok(split) => split,
+            Err(_) if !contents.starts_with("---\n") => {
+                if !tags.is_empty() || self.source.is_some() || self.pin {
+                    warn_without_front_matter(path);
+                }
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
 
-            if !new_tags.is_empty() {
-                let updated_tags = existing_tags
-                    .into_iter()
-                    .chain(new_tags)
-                    .collect::<Vec<_>>()
-                    .join(", ");
-                contents = contents.replace(&captures[0], &format!("tags: [{}]", updated_tags));
+        if config.case_insensitive_tags {
+            meta.lowercase_tags();
+        }
+        meta.add_tags(tags);
+        if let Some(source) = self.source.as_deref().map(str::trim) {
+            if !source.is_empty() {
+                add_source(&mut meta.extra, source);
             }
-        } else {
-            return Err(Error::CannotParseMetaData);
+        }
+        if meta.title.trim().is_empty() {
+            meta.title = title.to_owned();
+        }
+        if self.pin || self.unpin {
+            meta.pinned = self.pin;
         }
 
-        fs::write(&path, contents).map_err(|_| Error::CannotWriteToFile(path.clone()))?;
+        if meta.created.is_none() {
+            let created = fs::metadata(path)
+                .and_then(|metadata| metadata.created().or_else(|_| metadata.modified()))
+                .map(DateTime::<Local>::from)
+                .unwrap_or(now);
+            meta.created = Some(iso_timestamp(created));
+        }
+        meta.updated = Some(iso_timestamp(now));
 
-        Ok(())
+        Ok(Some((meta, body.to_owned())))
+    }
+}
+
+/// Builds what a note is written with once `lines` are appended to it.
+///
+/// A new note starts with its front matter unless it goes without, and an existing
+/// note gets its `updated` front matter back in front of its body, or is simply
+/// followed by the lines when it has none.
+fn written(
+    note: &Note,
+    existing: &str,
+    updated: Option<(FrontMatter, String)>,
+    lines: &str,
+    has_front_matter: bool,
+) -> String {
+    if has_front_matter && existing.is_empty() {
+        note.to_markdown()
+    } else if let Some((meta, body)) = updated {
+        format!("{}{body}{lines}", meta.render())
+    } else {
+        format!("{existing}{lines}")
+    }
+}
+
+/// Tells the user that the tags, source and pin of a note without front matter are
+/// left out.
+fn warn_without_front_matter(path: &Path) {
+    eprintln!(
+        "warning: {} has no front matter, so its tags, source and pin are left out",
+        path.display()
+    );
+}
+
+/// Adds a source to the `source:` field of some front matter, turning the field into
+/// a list once a note has several sources.
+fn add_source(extra: &mut Mapping, source: &str) {
+    let key = Value::from("source");
+    let value = match extra.remove(&key) {
+        None | Some(Value::Null) => Value::from(source),
+        Some(Value::Sequence(mut sources)) => {
+            if !sources
+                .iter()
+                .any(|existing| existing.as_str() == Some(source))
+            {
+                sources.push(Value::from(source));
+            }
+            Value::Sequence(sources)
+        }
+        Some(existing) if existing.as_str() == Some(source) => existing,
+        Some(existing) => Value::Sequence(vec![existing, Value::from(source)]),
+    };
+
+    extra.insert(key, value);
+}
+
+/// Links to a source as Markdown, named after the host of a URL, e.g.
+/// "([doc.rust-lang.org](https://doc.rust-lang.org/book/))". A source which isn't a
+/// URL is only put within parentheses.
+fn source_link(source: &str) -> String {
+    let host = source
+        .split_once("://")
+        .map(|(_, rest)| rest.split(['/', '?', '#']).next().unwrap_or(rest))
+        .filter(|host| !host.is_empty());
+
+    match host {
+        Some(host) => format!(
+            "([{}]({source}))",
+            host.strip_prefix("www.").unwrap_or(host)
+        ),
+        None => format!("({source})"),
+    }
+}
+
+/// Creates a note from its first bullets, created at the given time.
+fn new_note(
+    date: NaiveDate,
+    title: &str,
+    tags: &[String],
+    bullets: Vec<String>,
+    now: &str,
+) -> Note {
+    Note {
+        created: Some(now.to_owned()),
+        updated: Some(now.to_owned()),
+        ..Note::new(date, title, tags, bullets)
+    }
+}
+
+/// Formats a time as an ISO-8601 timestamp with second precision, e.g.
+/// "2024-10-07T09:00:00+02:00".
+pub fn iso_timestamp(time: DateTime<Local>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+/// Turns every non-empty line of some input into a bullet.
+fn lines_to_bullets(input: &str) -> Vec<String> {
+    input
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Removes the first line which is a `# ` heading, returning its text.
+fn take_heading(lines: &mut Vec<String>) -> Option<String> {
+    let index = lines.iter().position(|line| line.starts_with("# "))?;
+    let heading = lines.remove(index);
+
+    Some(heading[2..].trim().to_owned()).filter(|heading| !heading.is_empty())
+}
+
+/// Matches a #hashtag at the start of a bullet or following whitespace, so URL
+/// fragments such as `http://x#frag` are left alone.
+static INLINE_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|\s)#(\w[\w-]*)").expect("inline tag pattern is valid"));
+
+/// Matches an inline code span, within which #hashtags are ignored.
+static CODE_SPAN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"`[^`]*`").expect("code span pattern is valid"));
+
+/// Returns every inline #hashtag within some content, without the `#` and
+/// without duplicates.
+///
+/// Hashtags within code spans, right after other characters (e.g. URL fragments) or
+/// made only of digits (e.g. issue numbers) are not tags.
+fn parse_inline_tags(content: &str) -> Vec<String> {
+    let content = CODE_SPAN.replace_all(content, " ");
+    let mut tags = Vec::new();
+
+    for captures in INLINE_TAG.captures_iter(&content) {
+        let tag = captures[2].to_owned();
+        if !tag.chars().all(|c| c.is_ascii_digit()) && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+/// Removes the inline #hashtags from a bullet, leaving code spans untouched.
+fn strip_inline_tags(bullet: &str) -> String {
+    let tags = parse_inline_tags(bullet);
+    bullet
+        .split(' ')
+        .filter(|word| {
+            !word
+                .strip_prefix('#')
+                .map(|tag| tag.trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '-'))
+                .is_some_and(|tag| tags.iter().any(|found| found == tag))
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim()
+        .to_owned()
+}
+
+/// Checks whether a bullet is already among the existing bullets of a note, comparing
+/// trimmed content and ignoring any timestamp prefix.
+fn is_duplicate(existing: &[String], bullet: &str) -> bool {
+    let bullet = strip_timestamp(bullet.trim());
+    existing
+        .iter()
+        .any(|line| strip_timestamp(line.trim()) == bullet)
+}
+
+/// Strips a leading "[HH:MM] " timestamp from a bullet, if any.
+fn strip_timestamp(bullet: &str) -> &str {
+    match bullet.as_bytes() {
+        [b'[', h1, h2, b':', m1, m2, b']', b' ', ..]
+            if [h1, h2, m1, m2].iter().all(|digit| digit.is_ascii_digit()) =>
+        {
+            bullet[8..].trim_start()
+        }
+        _ => bullet,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use chrono::{Local, NaiveDate};
+    use clap::Parser;
+
+    use serde_yaml::{Mapping, Value};
+
+    use super::{
+        add_source, is_duplicate, new_note, parse_inline_tags, source_link, strip_inline_tags,
+        take_heading, written, Entry, Extension,
+    };
+    use crate::{
+        config::Config,
+        error::Error,
+        front_matter::{lowercase_tags, FrontMatter},
+    };
+
+    #[derive(Parser)]
+    struct Cli {
+        #[clap(flatten)]
+        entry: Entry,
+    }
+
+    fn parse(args: &[&str]) -> Entry {
+        Cli::parse_from(["til", "note"].iter().chain(args)).entry
+    }
+
+    #[test]
+    fn omitted_tags_are_empty() {
+        assert!(parse(&[]).tags(',').is_empty());
+    }
+
+    #[test]
+    fn empty_tags_are_ignored() {
+        let entry = parse(&["--tags", ""]);
+        assert!(entry.tags(',').is_empty());
+        assert!(new_note(
+            NaiveDate::default(),
+            "default",
+            &entry.tags(','),
+            Vec::new(),
+            "now"
+        )
+        .to_markdown()
+        .contains("tags: []\n"));
+
+        let entry = parse(&["--tags", "a,,b"]);
+        assert_eq!(entry.tags(','), vec!["a", "b"]);
+        assert!(new_note(
+            NaiveDate::default(),
+            "default",
+            &entry.tags(','),
+            Vec::new(),
+            "now"
+        )
+        .to_markdown()
+        .contains("tags: [a, b]\n"));
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_collapsed() {
+        let spaced = parse(&["--tags", " a,  b ,a, two   words"]);
+        let packed = parse(&["--tags", "a,b,two words"]);
+
+        assert_eq!(spaced.tags(','), ["a", "b", "two words"]);
+        assert_eq!(
+            new_note(
+                NaiveDate::default(),
+                "default",
+                &spaced.tags(','),
+                Vec::new(),
+                "now"
+            )
+            .to_markdown(),
+            new_note(
+                NaiveDate::default(),
+                "default",
+                &packed.tags(','),
+                Vec::new(),
+                "now"
+            )
+            .to_markdown()
+        );
+        assert_eq!(
+            parse(&["--tags", "a  b", "--tags", "c"]).tags(' '),
+            ["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn new_notes_write_unindented_front_matter() {
+        let entry = Entry {
+            content: vec!["note".to_string()],
+            title: None,
+            file: None,
+            tags: vec!["tag1".to_string(), "tag2".to_string()],
+            date: None,
+            edit: false,
+            timestamp: false,
+            bullet_prefix: None,
+            no_dup: false,
+            strip_tags: false,
+            title_from_h1: false,
+            topic: None,
+            ext: Extension::Md,
+            template: None,
+            no_front_matter: false,
+            source: None,
+            link_source: false,
+            pin: false,
+            unpin: false,
+        };
+
+        assert_eq!(
+            new_note(
+                NaiveDate::default(),
+                "rust",
+                &entry.tags(','),
+                Vec::new(),
+                "2024-10-07T09:00:00+02:00"
+            )
+            .to_markdown()
+            .as_bytes(),
+            b"---\ntitle: \"rust\"\ntags: [tag1, tag2]\ncreated: 2024-10-07T09:00:00+02:00\nupdated: 2024-10-07T09:00:00+02:00\n---\n\n"
+        );
+    }
+
+    #[test]
+    fn blank_content_is_rejected_without_creating_the_note() {
+        let root_dir = env::temp_dir().join(format!("til-empty-{}", std::process::id()));
+        let entry = Cli::parse_from(["til", "", "  \t", "--date", "01-01-1971"]).entry;
+        let path = entry
+            .note_path(
+                &root_dir,
+                NaiveDate::from_ymd_opt(1971, 1, 1).unwrap(),
+                "default",
+            )
+            .unwrap();
+
+        assert!(matches!(
+            entry.write(&root_dir, 0, true, false),
+            Err(Error::EmptyContent)
+        ));
+        assert!(!path.exists());
+        assert_eq!(parse(&["", " "]).bullets().unwrap(), ["note"]);
+    }
+
+    #[test]
+    fn appending_keeps_the_title() {
+        let path = env::temp_dir().join(format!("til-title-{}.md", std::process::id()));
+        let entry = parse(&["-t", "Rust lifetimes", "--tags", "rust"]);
+        let note = new_note(
+            NaiveDate::default(),
+            "Rust lifetimes",
+            &entry.tags(','),
+            vec!["first".to_string()],
+            "now",
+        )
+        .to_markdown();
+        fs::write(&path, note).unwrap();
+
+        let entry = parse(&["--tags", "borrowing"]);
+        let (meta, body) = entry
+            .updated_meta(
+                &path,
+                "default",
+                &entry.tags(','),
+                &Config::default(),
+                Local::now(),
+            )
+            .unwrap()
+            .unwrap();
+        fs::write(&path, format!("{}{body}- second\n", meta.render())).unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        let meta = FrontMatter::parse(&contents).unwrap();
+
+        assert_eq!(meta.title, "Rust lifetimes");
+        assert_eq!(meta.tags, ["rust", "borrowing"]);
+        assert!(contents.ends_with("- first\n- second\n"));
+    }
+
+    #[test]
+    fn notes_without_front_matter_start_with_their_bullets() {
+        let entry = parse(&["--no-frontmatter", "--tags", "rust"]);
+        let note = new_note(
+            NaiveDate::default(),
+            "rust",
+            &entry.tags(','),
+            vec!["note".to_owned()],
+            "2024-10-07T09:00:00+02:00",
+        );
+
+        assert_eq!(written(&note, "", None, "- note\n", false), "- note\n");
+        assert_eq!(
+            written(&note, "- first\n", None, "- note\n", false),
+            "- first\n- note\n"
+        );
+
+        let path = env::temp_dir().join(format!("til-no-front-{}.md", std::process::id()));
+        fs::write(&path, "- first\n").unwrap();
+        let updated = entry.updated_meta(
+            &path,
+            "rust",
+            &entry.tags(','),
+            &Config::default(),
+            Local::now(),
+        );
+        fs::remove_file(&path).unwrap();
+
+        assert!(updated.unwrap().is_none());
+    }
+
+    #[test]
+    fn mixed_case_tags_are_merged_when_ignoring_case() {
+        let path = env::temp_dir().join(format!("til-tag-case-{}.md", std::process::id()));
+        fs::write(
+            &path,
+            "---\ntitle: \"rust\"\ntags: [Rust, Async, rust]\n---\n\n- first\n",
+        )
+        .unwrap();
+        let entry = parse(&["--tags", "RUST,Tokio"]);
+        let config = Config {
+            case_insensitive_tags: true,
+            ..Config::default()
+        };
+
+        let (meta, _) = entry
+            .updated_meta(
+                &path,
+                "rust",
+                &lowercase_tags(&entry.tags(',')),
+                &config,
+                Local::now(),
+            )
+            .unwrap()
+            .unwrap();
+        let (sensitive, _) = entry
+            .updated_meta(
+                &path,
+                "rust",
+                &entry.tags(','),
+                &Config::default(),
+                Local::now(),
+            )
+            .unwrap()
+            .unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(meta.tags, ["rust", "async", "tokio"]);
+        assert_eq!(sensitive.tags, ["Rust", "Async", "rust", "RUST", "Tokio"]);
+    }
+
+    #[test]
+    fn duplicates_ignore_whitespace_and_timestamps() {
+        let existing = vec!["[09:15] learned rust".to_string(), "borrowing ".to_string()];
+
+        assert!(is_duplicate(&existing, "learned rust"));
+        assert!(is_duplicate(&existing, "[17:40]  borrowing"));
+        assert!(!is_duplicate(&existing, "learned rust lifetimes"));
+    }
+
+    #[test]
+    fn inline_tags_skip_code_spans_urls_and_numbers() {
+        assert_eq!(
+            parse_inline_tags("#rust futures are lazy #async, see #42 and http://x#frag #rust"),
+            ["rust", "async"]
+        );
+        assert!(parse_inline_tags("use `#[derive(Debug)]` or `#tag`").is_empty());
+        assert_eq!(
+            strip_inline_tags("#rust futures are lazy #async, see `#tag`"),
+            "futures are lazy see `#tag`"
+        );
+    }
+
+    #[test]
+    fn first_heading_becomes_the_title() {
+        let mut lines = vec![
+            "intro".to_string(),
+            "# Rust lifetimes".to_string(),
+            "# Second".to_string(),
+        ];
+
+        assert_eq!(take_heading(&mut lines).as_deref(), Some("Rust lifetimes"));
+        assert_eq!(lines, ["intro", "# Second"]);
+        assert!(take_heading(&mut vec!["## not a title".to_string()]).is_none());
+    }
+
+    #[test]
+    fn sources_become_a_list_once_there_are_several() {
+        let mut extra = Mapping::new();
+        add_source(&mut extra, "https://blog.rust-lang.org");
+        assert_eq!(extra["source"], Value::from("https://blog.rust-lang.org"));
+
+        add_source(&mut extra, "https://blog.rust-lang.org");
+        add_source(&mut extra, "https://doc.rust-lang.org");
+        assert_eq!(
+            extra["source"],
+            Value::Sequence(vec![
+                Value::from("https://blog.rust-lang.org"),
+                Value::from("https://doc.rust-lang.org")
+            ])
+        );
+
+        assert_eq!(
+            source_link("https://www.example.com/post?id=1"),
+            "([example.com](https://www.example.com/post?id=1))"
+        );
+        assert_eq!(source_link("the book"), "(the book)");
     }
 }
@@ -1,19 +1,59 @@
-use std::{fmt::Display, path::PathBuf};
+use std::{fmt::Display, io, path::PathBuf};
 
 type Message = String;
 type Directory = String;
+type Date = String;
+type Query = String;
+type Pattern = String;
+type Editor = String;
+type Program = String;
+type GitCommand = String;
+type Title = String;
+type Tag = String;
+type Topic = String;
 
 #[derive(Debug, Default)]
 pub enum Error {
     CannotBuildPath,
     CannotFindDir(Directory),
-    CannotCreateDir(Directory),
+    CannotCreateDir(Directory, io::Error),
     CannotProcessArgs,
-    CannotOpenOrCreatePath(PathBuf),
-    CannotWriteToFile(PathBuf),
+    CannotReadStdin,
+    NoContent,
+    EmptyContent,
+    CannotLaunchEditor(Editor),
+    EditorFailed(Editor),
+    EmptyEditorContent,
+    CannotLaunchFileManager(Program),
+    CannotOpenOrCreatePath(PathBuf, io::Error),
+    CannotWriteToFile(PathBuf, io::Error),
     CannotParseMetaData,
-    CannotReadFile(PathBuf),
-    InvalidDateFormat,
+    CannotParseConfig(PathBuf, Message),
+    InvalidTemplate(PathBuf, Message),
+    CannotReadFile(PathBuf, io::Error),
+    CannotRemoveFile(PathBuf, io::Error),
+    CannotFindFile(PathBuf),
+    CannotFindNote(PathBuf, Title),
+    FileExists(PathBuf),
+    DateDirExists(PathBuf),
+    AlreadyArchived(PathBuf),
+    NoBulletToAmend(PathBuf),
+    NoNotesFound(Date),
+    NoMatchesFound(Query),
+    GitCommand(GitCommand, Message),
+    NotARepository(PathBuf),
+    MergeConflict(PathBuf),
+    InvalidPattern(Pattern, Message),
+    CannotParseDate(Date),
+    InvalidDateFormat(Pattern),
+    InvalidBulletPrefix(String),
+    InvalidTitle(Title),
+    InvalidTag(Tag),
+    InvalidTopic(Topic),
+    TagNotFound(Tag, PathBuf),
+    CannotDecrypt(PathBuf),
+    NoPassphrase,
+    WrongPassphrase,
     Custom(Message),
     #[default]
     Default,
@@ -21,30 +61,205 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-impl std::error::Error for Error {}
+/// The exit code of any failure without a more specific code.
+pub const EXIT_FAILURE: i32 = 1;
+/// The exit code of an invalid command line, the same clap exits with.
+pub const EXIT_USAGE: i32 = 2;
+/// The exit code when a note, date, directory or match cannot be found.
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// The exit code when a file or directory cannot be read, written or created.
+pub const EXIT_IO: i32 = 4;
+/// The exit code when some input cannot be parsed or is not valid.
+pub const EXIT_INVALID: i32 = 5;
+
+impl Error {
+    /// Returns the code the process exits with on this error, so scripts can tell
+    /// the categories of errors apart.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::CannotProcessArgs => EXIT_USAGE,
+            Error::CannotFindDir(_)
+            | Error::CannotFindFile(_)
+            | Error::CannotFindNote(_, _)
+            | Error::NoBulletToAmend(_)
+            | Error::NoNotesFound(_)
+            | Error::NoMatchesFound(_)
+            | Error::NotARepository(_)
+            | Error::TagNotFound(_, _) => EXIT_NOT_FOUND,
+            Error::CannotBuildPath
+            | Error::CannotCreateDir(_, _)
+            | Error::CannotReadStdin
+            | Error::CannotOpenOrCreatePath(_, _)
+            | Error::CannotWriteToFile(_, _)
+            | Error::CannotReadFile(_, _)
+            | Error::CannotRemoveFile(_, _) => EXIT_IO,
+            Error::NoContent
+            | Error::EmptyContent
+            | Error::EmptyEditorContent
+            | Error::CannotParseMetaData
+            | Error::CannotParseConfig(_, _)
+            | Error::InvalidTemplate(_, _)
+            | Error::FileExists(_)
+            | Error::DateDirExists(_)
+            | Error::AlreadyArchived(_)
+            | Error::InvalidPattern(_, _)
+            | Error::CannotParseDate(_)
+            | Error::InvalidDateFormat(_)
+            | Error::InvalidBulletPrefix(_)
+            | Error::InvalidTitle(_)
+            | Error::InvalidTag(_)
+            | Error::InvalidTopic(_)
+            | Error::CannotDecrypt(_)
+            | Error::NoPassphrase
+            | Error::WrongPassphrase => EXIT_INVALID,
+            Error::CannotLaunchEditor(_)
+            | Error::EditorFailed(_)
+            | Error::CannotLaunchFileManager(_)
+            | Error::GitCommand(_, _)
+            | Error::MergeConflict(_)
+            | Error::Custom(_)
+            | Error::Default => EXIT_FAILURE,
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::CannotOpenOrCreatePath(_, err)
+            | Error::CannotCreateDir(_, err)
+            | Error::CannotWriteToFile(_, err)
+            | Error::CannotReadFile(_, err)
+            | Error::CannotRemoveFile(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::CannotBuildPath => f.write_str("cannot construct path"),
             Error::CannotFindDir(dir) => f.write_fmt(format_args!("cannot find {} directory", dir)),
-            Error::CannotCreateDir(dir) => {
+            Error::CannotCreateDir(dir, _) => {
                 f.write_fmt(format_args!("cannot create {} directory", dir))
             }
             Error::CannotProcessArgs => f.write_str("cannot process command-line arguments"),
-            Error::CannotOpenOrCreatePath(path) => {
+            Error::CannotReadStdin => f.write_str("cannot read from stdin"),
+            Error::NoContent => {
+                f.write_str("no content was provided, pass it as an argument or through stdin")
+            }
+            Error::EmptyContent => {
+                f.write_str("content is empty or only whitespace, nothing was written")
+            }
+            Error::CannotLaunchEditor(editor) => {
+                f.write_fmt(format_args!("cannot launch editor {}", editor))
+            }
+            Error::EditorFailed(editor) => {
+                f.write_fmt(format_args!("editor {} exited unsuccessfully", editor))
+            }
+            Error::CannotLaunchFileManager(program) => {
+                f.write_fmt(format_args!("cannot launch file manager {}", program))
+            }
+            Error::EmptyEditorContent => f.write_str("note is empty, nothing was written"),
+            Error::CannotOpenOrCreatePath(path, _) => {
                 f.write_fmt(format_args!("cannot open or create {}", path.display()))
             }
-            Error::CannotWriteToFile(file) => {
+            Error::CannotWriteToFile(file, _) => {
                 f.write_fmt(format_args!("cannot write to {}", file.display()))
             }
-            Error::CannotReadFile(file) => {
+            Error::CannotReadFile(file, _) => {
                 f.write_fmt(format_args!("cannot read file {}", file.display()))
             }
+            Error::CannotRemoveFile(file, _) => {
+                f.write_fmt(format_args!("cannot remove {}", file.display()))
+            }
+            Error::CannotFindFile(file) => {
+                f.write_fmt(format_args!("cannot find file {}", file.display()))
+            }
+            Error::CannotFindNote(file, closest) => f.write_fmt(format_args!(
+                "cannot find file {}, did you mean \"{}\"? pass --fuzzy to show it",
+                file.display(),
+                closest
+            )),
+            Error::NoBulletToAmend(file) => {
+                f.write_fmt(format_args!("{} has no bullet to amend", file.display()))
+            }
+            Error::DateDirExists(directory) => f.write_fmt(format_args!(
+                "cannot migrate, {} already exists: merge its notes with `til dedupe-dates` first",
+                directory.display()
+            )),
+            Error::AlreadyArchived(directory) => f.write_fmt(format_args!(
+                "cannot archive, {} already exists: merge it with the date directory first",
+                directory.display()
+            )),
+            Error::FileExists(file) => f.write_fmt(format_args!(
+                "{} already exists, pass --force to overwrite it",
+                file.display()
+            )),
+            Error::NoNotesFound(date) => {
+                f.write_fmt(format_args!("no notes were found from {}", date))
+            }
+            Error::NoMatchesFound(query) => {
+                f.write_fmt(format_args!("no notes were found matching \"{}\"", query))
+            }
+            Error::GitCommand(command, msg) => {
+                f.write_fmt(format_args!("{} failed: {}", command, msg))
+            }
+            Error::NotARepository(dir) => f.write_fmt(format_args!(
+                "{} is not a git repository, run `til init --git` and add a remote to sync notes",
+                dir.display()
+            )),
+            Error::MergeConflict(dir) => f.write_fmt(format_args!(
+                "cannot sync, resolve the conflicts in {} then run `git rebase --continue`",
+                dir.display()
+            )),
+            Error::InvalidPattern(pattern, msg) => {
+                f.write_fmt(format_args!("invalid pattern \"{}\": {}", pattern, msg))
+            }
             Error::CannotParseMetaData => f.write_str("cannot parse metadata"),
-            Error::InvalidDateFormat => {
-                f.write_str("cannot parse date format, must use format MM-DD-YYYY")
+            Error::CannotParseConfig(file, msg) => f.write_fmt(format_args!(
+                "cannot parse config {}: {}",
+                file.display(),
+                msg
+            )),
+            Error::InvalidTemplate(file, msg) => {
+                f.write_fmt(format_args!("invalid template {}: {}", file.display(), msg))
+            }
+            Error::CannotParseDate(date) => f.write_fmt(format_args!(
+                "cannot parse date \"{}\", try MM-DD-YYYY, YYYY-MM-DD or \"yesterday\"",
+                date
+            )),
+            Error::InvalidBulletPrefix(prefix) => f.write_fmt(format_args!(
+                "cannot prefix bullets with {:?}, pass something other than whitespace on a single line",
+                prefix
+            )),
+            Error::InvalidDateFormat(format) => f.write_fmt(format_args!(
+                "cannot format dates with \"{}\", use chrono's specifiers such as \"%b %-d, %Y\"",
+                format
+            )),
+            Error::InvalidTitle(title) => {
+                f.write_fmt(format_args!("cannot use \"{}\" as a title", title))
             }
+            Error::InvalidTag(tag) => f.write_fmt(format_args!("cannot use \"{}\" as a tag", tag)),
+            Error::InvalidTopic(topic) => {
+                f.write_fmt(format_args!("cannot use \"{}\" as a topic", topic))
+            }
+            Error::TagNotFound(tag, file) => f.write_fmt(format_args!(
+                "{} has no tag \"{}\", pass --ignore-missing to skip it",
+                file.display(),
+                tag
+            )),
+            Error::CannotDecrypt(file) => f.write_fmt(format_args!(
+                "cannot decrypt {}, the passphrase is wrong or the note is corrupted",
+                file.display()
+            )),
+            Error::NoPassphrase => f.write_str(
+                "an encrypted note needs a passphrase, set TIL_PASSPHRASE or run til in a terminal",
+            ),
+            Error::WrongPassphrase => f.write_str(
+                "the passphrase doesn't match the one notes are encrypted with, nothing was written",
+            ),
             Error::Custom(msg) => f.write_str(msg),
             Error::Default => f.write_str("something wrong happened"),
         }
@@ -65,7 +280,9 @@ impl From<String> for Error {
 
 #[cfg(test)]
 mod tests {
-    use crate::error::Error;
+    use std::{error::Error as _, io};
+
+    use crate::error::{Error, EXIT_FAILURE, EXIT_INVALID, EXIT_IO, EXIT_NOT_FOUND, EXIT_USAGE};
 
     #[test]
     fn error_variants_return_proper_messages() {
@@ -76,26 +293,148 @@ mod tests {
                 "cannot find parent directory",
             ),
             (
-                Error::CannotCreateDir("./til/notes".to_string()),
+                Error::CannotCreateDir("./til/notes".to_string(), io::Error::other("test")),
                 "cannot create ./til/notes directory",
             ),
             (
                 Error::CannotProcessArgs,
                 "cannot process command-line arguments",
             ),
+            (Error::CannotReadStdin, "cannot read from stdin"),
+            (
+                Error::NoContent,
+                "no content was provided, pass it as an argument or through stdin",
+            ),
+            (
+                Error::EmptyContent,
+                "content is empty or only whitespace, nothing was written",
+            ),
+            (
+                Error::CannotLaunchEditor("vi".to_string()),
+                "cannot launch editor vi",
+            ),
+            (
+                Error::EditorFailed("vi".to_string()),
+                "editor vi exited unsuccessfully",
+            ),
+            (
+                Error::CannotLaunchFileManager("xdg-open".to_string()),
+                "cannot launch file manager xdg-open",
+            ),
+            (
+                Error::EmptyEditorContent,
+                "note is empty, nothing was written",
+            ),
             (
-                Error::CannotOpenOrCreatePath("src/test".into()),
+                Error::CannotOpenOrCreatePath("src/test".into(), io::Error::other("test")),
                 "cannot open or create src/test",
             ),
             (
-                Error::CannotWriteToFile("src/test".into()),
+                Error::CannotWriteToFile("src/test".into(), io::Error::other("test")),
                 "cannot write to src/test",
             ),
             (Error::CannotParseMetaData, "cannot parse metadata"),
             (
-                Error::CannotReadFile("src/test".into()),
+                Error::CannotParseConfig("config.toml".into(), "unknown field".to_string()),
+                "cannot parse config config.toml: unknown field",
+            ),
+            (
+                Error::InvalidTemplate("meta.yml".into(), "no {{title}}".to_string()),
+                "invalid template meta.yml: no {{title}}",
+            ),
+            (
+                Error::CannotReadFile("src/test".into(), io::Error::other("test")),
                 "cannot read file src/test",
             ),
+            (
+                Error::CannotRemoveFile("src/test".into(), io::Error::other("test")),
+                "cannot remove src/test",
+            ),
+            (
+                Error::CannotFindFile("src/test".into()),
+                "cannot find file src/test",
+            ),
+            (
+                Error::CannotFindNote("databse.md".into(), "database".to_string()),
+                "cannot find file databse.md, did you mean \"database\"? pass --fuzzy to show it",
+            ),
+            (
+                Error::NoBulletToAmend("src/test".into()),
+                "src/test has no bullet to amend",
+            ),
+            (
+                Error::DateDirExists("notes/2024-08-18".into()),
+                "cannot migrate, notes/2024-08-18 already exists: merge its notes with `til dedupe-dates` first",
+            ),
+            (
+                Error::AlreadyArchived("notes/archive/08-18-2024".into()),
+                "cannot archive, notes/archive/08-18-2024 already exists: merge it with the date directory first",
+            ),
+            (
+                Error::FileExists("src/test".into()),
+                "src/test already exists, pass --force to overwrite it",
+            ),
+            (
+                Error::NoNotesFound("08-18-2024".to_string()),
+                "no notes were found from 08-18-2024",
+            ),
+            (
+                Error::NoMatchesFound("borrow checker".to_string()),
+                "no notes were found matching \"borrow checker\"",
+            ),
+            (
+                Error::GitCommand("git commit".to_string(), "nothing to commit".to_string()),
+                "git commit failed: nothing to commit",
+            ),
+            (
+                Error::NotARepository("notes".into()),
+                "notes is not a git repository, run `til init --git` and add a remote to sync notes",
+            ),
+            (
+                Error::MergeConflict("notes".into()),
+                "cannot sync, resolve the conflicts in notes then run `git rebase --continue`",
+            ),
+            (
+                Error::InvalidPattern("[".to_string(), "unclosed class".to_string()),
+                "invalid pattern \"[\": unclosed class",
+            ),
+            (
+                Error::CannotParseDate("someday".to_string()),
+                "cannot parse date \"someday\", try MM-DD-YYYY, YYYY-MM-DD or \"yesterday\"",
+            ),
+            (
+                Error::InvalidBulletPrefix(" ".to_string()),
+                "cannot prefix bullets with \" \", pass something other than whitespace on a single line",
+            ),
+            (
+                Error::InvalidDateFormat("%H".to_string()),
+                "cannot format dates with \"%H\", use chrono's specifiers such as \"%b %-d, %Y\"",
+            ),
+            (
+                Error::InvalidTitle("..".to_string()),
+                "cannot use \"..\" as a title",
+            ),
+            (Error::InvalidTag(" ".to_string()), "cannot use \" \" as a tag"),
+            (
+                Error::InvalidTopic("rust/..".to_string()),
+                "cannot use \"rust/..\" as a topic",
+            ),
+            (
+                Error::TagNotFound("rust".to_string(), "src/test".into()),
+                "src/test has no tag \"rust\", pass --ignore-missing to skip it",
+            ),
+            (
+                Error::CannotDecrypt("src/test".into()),
+                "cannot decrypt src/test, the passphrase is wrong or the note is corrupted",
+            ),
+            (
+                Error::NoPassphrase,
+                "an encrypted note needs a passphrase, set TIL_PASSPHRASE or run til in a terminal",
+            ),
+            (
+                Error::WrongPassphrase,
+                "the passphrase doesn't match the one notes are encrypted with, nothing was written",
+            ),
             ("custom message".into(), "custom message"),
             (Error::default(), "something wrong happened"),
         ];
@@ -104,4 +443,49 @@ mod tests {
             .iter()
             .for_each(|(err, msg)| assert_eq!(format!("{err}"), msg.to_string()));
     }
+
+    #[test]
+    fn errors_exit_with_their_category() {
+        let codes = [
+            (
+                Error::NoNotesFound("08-18-2024".to_string()),
+                EXIT_NOT_FOUND,
+            ),
+            (Error::CannotFindFile("src/test".into()), EXIT_NOT_FOUND),
+            (
+                Error::CannotReadFile(
+                    "src/test".into(),
+                    io::Error::from(io::ErrorKind::PermissionDenied),
+                ),
+                EXIT_IO,
+            ),
+            (Error::CannotParseDate("someday".to_string()), EXIT_INVALID),
+            (Error::CannotProcessArgs, EXIT_USAGE),
+            (Error::Custom("test".to_string()), EXIT_FAILURE),
+        ];
+
+        for (err, code) in codes {
+            assert_eq!(err.exit_code(), code, "{err}");
+        }
+    }
+
+    #[test]
+    fn file_errors_keep_their_source() {
+        let err = Error::CannotWriteToFile(
+            "src/test".into(),
+            io::Error::from(io::ErrorKind::PermissionDenied),
+        );
+
+        assert_eq!(
+            err.source().map(|source| source.to_string()),
+            Some(io::Error::from(io::ErrorKind::PermissionDenied).to_string())
+        );
+        assert!(Error::CannotCreateDir(
+            "notes".to_string(),
+            io::Error::from(io::ErrorKind::PermissionDenied)
+        )
+        .source()
+        .is_some());
+        assert!(Error::CannotParseMetaData.source().is_none());
+    }
 }
@@ -0,0 +1,425 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::NaiveDate;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+
+use crate::{
+    error::Error,
+    notes::{self, Note},
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Export {
+    /// Format of the exported document
+    #[clap(long, value_enum, default_value_t = Format::Markdown)]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// A single Markdown document with a heading per day and note
+    Markdown,
+    /// A self-contained HTML page with the notes grouped by day and filterable by tag
+    Html,
+    /// Tab-separated Anki flashcards, one per bullet, split into front and back by "::"
+    Anki,
+    /// A TOML document with an array of notes
+    Toml,
+    /// A YAML document with a list of notes
+    Yaml,
+    /// JSON Lines, one `{ date, title, tags, bullets }` object per note, written as each note is read
+    Jsonl,
+}
+
+/// Every note of a single day, in the order they are exported.
+struct ExportedDay {
+    name: String,
+    notes: Vec<Note>,
+}
+
+/// Every note, as serialized to TOML or YAML.
+#[derive(Serialize)]
+struct Archive<'a> {
+    notes: Vec<ArchivedNote<'a>>,
+}
+
+/// A note within an archive, or a line of JSON Lines, dated as an ISO-8601 string
+/// such as "2024-10-06" whichever format its directory is named in.
+#[derive(Serialize)]
+struct ArchivedNote<'a> {
+    date: NaiveDate,
+    title: &'a str,
+    tags: &'a [String],
+    bullets: &'a [String],
+}
+
+impl Export {
+    /// Exports every note, in chronological order, into a single document written
+    /// to `out`.
+    ///
+    /// JSON Lines are streamed instead, each note being written and flushed as soon
+    /// as it is read, so that exporting years of notes never holds them all in
+    /// memory and the lines can be processed while the export runs.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::Custom` - If the notes cannot be serialized to TOML, YAML or JSON.
+    /// * `Error::CannotWriteToFile` - If the document cannot be written.
+    pub fn export(&self, root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+        let document = match self.format {
+            Format::Jsonl => return stream_json_lines(root_dir, out),
+            Format::Markdown => to_markdown(&collect(root_dir)?),
+            Format::Html => to_html(&collect(root_dir)?),
+            Format::Anki => to_anki(&collect(root_dir)?),
+            Format::Toml => toml::to_string(&to_archive(&collect(root_dir)?))
+                .map_err(|err| Error::Custom(err.to_string()))?,
+            Format::Yaml => serde_yaml::to_string(&to_archive(&collect(root_dir)?))
+                .map_err(|err| Error::Custom(err.to_string()))?,
+        };
+
+        out.write_all(document.as_bytes()).map_err(write_error)
+    }
+}
+
+/// Writes every note as a line of JSON as soon as it is read, flushing each line.
+/// The export stops quietly once the reading end of a pipe is closed, as with
+/// `til export --format jsonl | head`.
+fn stream_json_lines(root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+    for day in notes::days(root_dir)? {
+        for path in notes::notes_in(&day.path)? {
+            let contents = notes::read(&path)?;
+            let line = to_json_line(&Note::parse(day.date, &path, &contents))?;
+
+            match writeln!(out, "{line}").and_then(|_| out.flush()) {
+                Err(err) if err.kind() == io::ErrorKind::BrokenPipe => return Ok(()),
+                written => written.map_err(write_error)?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_error(err: io::Error) -> Error {
+    Error::CannotWriteToFile("stdout".into(), err)
+}
+
+/// Serializes a note as a single line of JSON.
+fn to_json_line(note: &Note) -> crate::error::Result<String> {
+    let note = ArchivedNote {
+        date: note.date,
+        title: &note.title,
+        tags: &note.tags,
+        bullets: &note.bullets,
+    };
+
+    serde_json::to_string(&note).map_err(|err| Error::Custom(err.to_string()))
+}
+
+/// Reads every note, grouped by day.
+fn collect(root_dir: &Path) -> crate::error::Result<Vec<ExportedDay>> {
+    let mut days = Vec::new();
+
+    for day in notes::days(root_dir)? {
+        let mut exported = Vec::new();
+
+        for path in notes::notes_in(&day.path)? {
+            let contents = notes::read(&path)?;
+            exported.push(Note::parse(day.date, &path, &contents));
+        }
+
+        if !exported.is_empty() {
+            days.push(ExportedDay {
+                name: day.name(),
+                notes: exported,
+            });
+        }
+    }
+
+    Ok(days)
+}
+
+/// Renders the days as Markdown, with a `## MM-DD-YYYY` heading per day listing the
+/// tags of its notes, and a `### title` heading per note.
+fn to_markdown(days: &[ExportedDay]) -> String {
+    let mut document = String::new();
+
+    for day in days {
+        let mut tags: Vec<&str> = Vec::new();
+        for tag in day.notes.iter().flat_map(|note| &note.tags) {
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+
+        if tags.is_empty() {
+            document.push_str(&format!("## {}\n\n", day.name));
+        } else {
+            document.push_str(&format!("## {} ({})\n\n", day.name, tags.join(", ")));
+        }
+
+        for note in &day.notes {
+            document.push_str(&format!("### {}\n\n", note.title));
+            let body = note.body.trim();
+            if !body.is_empty() {
+                document.push_str(&format!("{body}\n\n"));
+            }
+        }
+    }
+
+    document.truncate(document.trim_end().len());
+    document.push('\n');
+    document
+}
+
+const STYLESHEET: &str = "
+body { display: flex; margin: 0; font-family: system-ui, sans-serif; line-height: 1.5; color: #222; }
+nav { position: sticky; top: 0; align-self: flex-start; width: 12rem; padding: 1rem; }
+nav button { display: block; margin: 0 0 .25rem; border: 0; background: none; cursor: pointer; font: inherit; color: #555; }
+nav button.active { font-weight: bold; color: #000; }
+main { flex: 1; max-width: 48rem; padding: 1rem 2rem; }
+h2 { border-bottom: 1px solid #ddd; }
+.tags { font-size: .85rem; color: #777; }
+";
+
+const SCRIPT: &str = "
+document.querySelectorAll('nav button').forEach(button => button.addEventListener('click', () => {
+  const tag = button.dataset.tag;
+  document.querySelectorAll('nav button').forEach(other => other.classList.toggle('active', other === button));
+  document.querySelectorAll('article').forEach(note => {
+    note.hidden = tag !== '' && !JSON.parse(note.dataset.tags).includes(tag);
+  });
+  document.querySelectorAll('section').forEach(day => {
+    day.hidden = !day.querySelector('article:not([hidden])');
+  });
+}));
+";
+
+/// Renders the days as a self-contained HTML page, with each note's Markdown
+/// rendered to HTML and a sidebar of tags filtering the notes shown.
+fn to_html(days: &[ExportedDay]) -> String {
+    let mut tags: Vec<&str> = days
+        .iter()
+        .flat_map(|day| &day.notes)
+        .flat_map(|note| &note.tags)
+        .map(String::as_str)
+        .collect();
+    tags.sort_unstable();
+    tags.dedup();
+
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Today I Learned</title>\n<style>{STYLESHEET}</style>\n</head>\n<body>\n<nav>\n<button class=\"active\" data-tag=\"\">all</button>\n"
+    );
+    for tag in &tags {
+        page.push_str(&format!(
+            "<button data-tag=\"{0}\">{0}</button>\n",
+            escape(tag)
+        ));
+    }
+    page.push_str("</nav>\n<main>\n");
+
+    for day in days {
+        page.push_str(&format!("<section>\n<h2>{}</h2>\n", escape(&day.name)));
+
+        for note in &day.notes {
+            let tags = serde_json::to_string(&note.tags).unwrap_or_else(|_| "[]".to_owned());
+            page.push_str(&format!(
+                "<article data-tags=\"{}\">\n<h3>{}</h3>\n",
+                escape(&tags),
+                escape(&note.title)
+            ));
+            if !note.tags.is_empty() {
+                page.push_str(&format!(
+                    "<p class=\"tags\">{}</p>\n",
+                    escape(&note.tags.join(", "))
+                ));
+            }
+            pulldown_cmark::html::push_html(&mut page, pulldown_cmark::Parser::new(&note.body));
+            page.push_str("</article>\n");
+        }
+
+        page.push_str("</section>\n");
+    }
+
+    page.push_str(&format!(
+        "</main>\n<script>{SCRIPT}</script>\n</body>\n</html>\n"
+    ));
+    page
+}
+
+/// Escapes text so it can be used within HTML elements and quoted attributes.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Gathers every note into an archive to serialize.
+fn to_archive(days: &[ExportedDay]) -> Archive<'_> {
+    let notes = days
+        .iter()
+        .flat_map(|day| {
+            day.notes.iter().map(|note| ArchivedNote {
+                date: note.date,
+                title: &note.title,
+                tags: &note.tags,
+                bullets: &note.bullets,
+            })
+        })
+        .collect();
+
+    Archive { notes }
+}
+
+/// Renders every bullet as an Anki card within a tab-separated file, along with the
+/// headers telling Anki how to import it.
+///
+/// A bullet such as "front :: back" becomes a "Basic" card, while any other bullet
+/// becomes a "Cloze" card hiding the whole bullet, without a delimiter left dangling
+/// at either end. The tags of the note become the tags of its cards, with spaces
+/// replaced by `_` as Anki separates tags by spaces.
+fn to_anki(days: &[ExportedDay]) -> String {
+    let mut cards =
+        String::from("#separator:tab\n#html:false\n#notetype column:1\n#tags column:4\n");
+
+    for note in days.iter().flat_map(|day| &day.notes) {
+        let tags = note
+            .tags
+            .iter()
+            .map(|tag| tag.split_whitespace().collect::<Vec<_>>().join("_"))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        for bullet in &note.bullets {
+            let bullet = bullet.replace('\t', " ");
+            let (notetype, front, back) = match bullet.split_once("::") {
+                Some((front, back)) if !front.trim().is_empty() && !back.trim().is_empty() => {
+                    ("Basic", front.trim().to_owned(), back.trim())
+                }
+                _ => {
+                    let text = bullet.trim().trim_matches(':').trim();
+                    ("Cloze", format!("{{{{c1::{text}}}}}"), "")
+                }
+            };
+
+            cards.push_str(&format!("{notetype}\t{front}\t{back}\t{tags}\n"));
+        }
+    }
+
+    cards
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use chrono::NaiveDate;
+
+    use super::{to_anki, to_archive, to_html, to_json_line, to_markdown, ExportedDay};
+    use crate::notes::Note;
+
+    fn note(title: &str, tags: &[&str], body: &str) -> Note {
+        let date = NaiveDate::from_ymd_opt(2024, 10, 6).unwrap();
+
+        Note {
+            title: title.to_owned(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..Note::parse(date, Path::new("note.md"), body)
+        }
+    }
+
+    #[test]
+    fn markdown_has_a_heading_per_day_and_note() {
+        let days = vec![
+            ExportedDay {
+                name: "10-06-2024".to_string(),
+                notes: vec![
+                    note("default", &["rust"], "- borrowing"),
+                    note("async", &["rust", "async"], "- futures are lazy"),
+                ],
+            },
+            ExportedDay {
+                name: "10-07-2024".to_string(),
+                notes: vec![note("default", &[], "- pinning")],
+            },
+        ];
+
+        assert_eq!(
+            to_markdown(&days),
+            "## 10-06-2024 (rust, async)\n\n### default\n\n- borrowing\n\n### async\n\n- futures are lazy\n\n## 10-07-2024\n\n### default\n\n- pinning\n"
+        );
+    }
+
+    #[test]
+    fn html_renders_bullets_and_tag_filters() {
+        let days = vec![ExportedDay {
+            name: "10-06-2024".to_string(),
+            notes: vec![note("<rust>", &["rust"], "- `Box<T>` is a pointer")],
+        }];
+        let page = to_html(&days);
+
+        assert!(page.contains("<button data-tag=\"rust\">rust</button>"));
+        assert!(page.contains("<article data-tags=\"[&quot;rust&quot;]\">\n<h3>&lt;rust&gt;</h3>"));
+        assert!(page.contains("<li><code>Box&lt;T&gt;</code> is a pointer</li>"));
+    }
+
+    #[test]
+    fn anki_cards_are_split_on_the_delimiter() {
+        let days = vec![ExportedDay {
+            name: "10-06-2024".to_string(),
+            notes: vec![note(
+                "default",
+                &["rust", "two words"],
+                "- Box<T> :: a pointer to the heap\n  - nested\n- futures\tare lazy\n- :: no front",
+            )],
+        }];
+
+        assert_eq!(
+            to_anki(&days),
+            "#separator:tab\n#html:false\n#notetype column:1\n#tags column:4\n\
+             Basic\tBox<T>\ta pointer to the heap\trust two_words\n\
+             Cloze\t{{c1::futures are lazy}}\t\trust two_words\n\
+             Cloze\t{{c1::no front}}\t\trust two_words\n"
+        );
+    }
+
+    #[test]
+    fn archives_date_notes_as_iso_strings() {
+        let days = vec![ExportedDay {
+            name: "10-06-2024".to_string(),
+            notes: vec![note("default", &["rust"], "- borrowing\n\n- lifetimes")],
+        }];
+
+        assert_eq!(
+            toml::to_string(&to_archive(&days)).unwrap(),
+            "[[notes]]\ndate = \"2024-10-06\"\ntitle = \"default\"\ntags = [\"rust\"]\nbullets = [\"borrowing\", \"lifetimes\"]\n"
+        );
+        assert_eq!(
+            serde_yaml::to_string(&to_archive(&days)).unwrap(),
+            "notes:\n- date: 2024-10-06\n  title: default\n  tags:\n  - rust\n  bullets:\n  - borrowing\n  - lifetimes\n"
+        );
+    }
+
+    #[test]
+    fn json_lines_hold_a_whole_note_each() {
+        let line = to_json_line(&note("default", &["rust"], "- borrowing\n- lifetimes")).unwrap();
+
+        assert!(!line.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&line).unwrap(),
+            serde_json::json!({
+                "date": "2024-10-06",
+                "title": "default",
+                "tags": ["rust"],
+                "bullets": ["borrowing", "lifetimes"],
+            })
+        );
+    }
+}
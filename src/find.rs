@@ -0,0 +1,141 @@
+use std::{ops::Range, path::Path};
+
+use clap::Args;
+use rayon::prelude::*;
+
+use crate::{
+    error::Error,
+    index::{Index, IndexedNote},
+    page::Page,
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Find {
+    /// Text to look for within the notes
+    pub query: String,
+
+    /// Ignore case when matching the query
+    #[clap(short, long)]
+    pub ignore_case: bool,
+
+    /// Print this many bullets of the same note before and after each match
+    #[clap(short = 'C', long, value_name = "N")]
+    pub context: Option<usize>,
+
+    /// Print this many bullets of the same note before each match
+    #[clap(short = 'B', long, value_name = "N")]
+    pub before: Option<usize>,
+
+    /// Print this many bullets of the same note after each match
+    #[clap(short = 'A', long, value_name = "N")]
+    pub after: Option<usize>,
+
+    #[clap(flatten)]
+    pub page: Page,
+}
+
+impl Find {
+    /// Searches the body of every note for bullets containing the query.
+    ///
+    /// Each match is formatted as `MM-DD-YYYY/title.md: - bullet`, ordered
+    /// chronologically. The front matter of a note is never searched. The bullets
+    /// are read from the index and matched in parallel.
+    ///
+    /// With context, the surrounding bullets of the same note are formatted as
+    /// `MM-DD-YYYY/title.md- - bullet` and each run of adjacent bullets is separated
+    /// from the next by `--`, like grep does. Runs rather than matches are paginated.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read while indexing.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::NoMatchesFound` - If no bullet contains the query.
+    pub fn matches(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let query = self.normalize(&self.query);
+        let before = self.before.or(self.context).unwrap_or(0);
+        let after = self.after.or(self.context).unwrap_or(0);
+
+        // Encrypted notes are decrypted beforehand, so that the passphrase is asked
+        // for at most once rather than from every thread.
+        let notes = Index::load(root_dir)?
+            .notes
+            .into_iter()
+            .map(IndexedNote::opened)
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        let runs = notes
+            .par_iter()
+            .flat_map_iter(|note| {
+                let matched = note
+                    .bullets
+                    .iter()
+                    .map(|bullet| self.normalize(&format!("- {bullet}")).contains(&query))
+                    .collect::<Vec<_>>();
+
+                runs(&matched, before, after)
+                    .into_iter()
+                    .map(|run| {
+                        run.map(|index| {
+                            let separator = if matched[index] { ":" } else { "-" };
+                            format!("{}{separator} - {}", note.name, note.bullets[index])
+                        })
+                        .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        if runs.is_empty() {
+            return Err(Error::NoMatchesFound(self.query.clone()));
+        }
+
+        let runs = self.page.apply(runs);
+        if before == 0 && after == 0 {
+            return Ok(runs.concat());
+        }
+
+        Ok(runs.join(&"--".to_owned()))
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        if self.ignore_case {
+            text.to_lowercase()
+        } else {
+            text.to_owned()
+        }
+    }
+}
+
+/// Groups the matching bullets along with `before` and `after` bullets around them
+/// into runs of adjacent bullets, merging the runs which overlap or touch.
+fn runs(matched: &[bool], before: usize, after: usize) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = Vec::new();
+
+    for (index, _) in matched.iter().enumerate().filter(|(_, &matched)| matched) {
+        let run = index.saturating_sub(before)..(index + after + 1).min(matched.len());
+
+        match runs.last_mut() {
+            Some(last) if run.start <= last.end => last.end = last.end.max(run.end),
+            _ => runs.push(run),
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::runs;
+
+    #[test]
+    #[allow(clippy::single_range_in_vec_init)]
+    fn context_runs_merge_when_they_meet() {
+        let matched = [false, true, false, false, false, true, false, false];
+
+        assert_eq!(runs(&matched, 0, 0), [1..2, 5..6]);
+        assert_eq!(runs(&matched, 1, 1), [0..3, 4..7]);
+        assert_eq!(runs(&matched, 0, 3), [1..8]);
+        assert_eq!(runs(&matched, 5, 0), [0..6]);
+        assert!(runs(&[false, false], 2, 2).is_empty());
+    }
+}
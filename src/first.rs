@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::notes::{self, Day};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct First {
+    /// Number of bullets to show
+    #[clap(default_value_t = 10)]
+    pub count: usize,
+}
+
+impl First {
+    /// Collects the earliest bullets, oldest first, formatted as
+    /// `MM-DD-YYYY/title.md: - bullet`.
+    ///
+    /// Dates are read from the earliest one onwards, and no more notes are read once
+    /// enough bullets are collected.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    pub fn bullets(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        self.bullets_of(notes::days(root_dir)?)
+    }
+
+    fn bullets_of(&self, days: Vec<Day>) -> crate::error::Result<Vec<String>> {
+        let mut bullets = Vec::new();
+
+        'days: for day in days {
+            for path in notes::notes_in(&day.path)? {
+                let contents = notes::read(&path)?;
+                let name = day.note_name(&path);
+
+                for bullet in notes::bullets(&contents) {
+                    if bullets.len() == self.count {
+                        break 'days;
+                    }
+                    bullets.push(format!("{name}: - {bullet}"));
+                }
+            }
+        }
+
+        Ok(bullets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::First;
+    use crate::notes;
+
+    #[test]
+    fn earliest_bullets_come_first() {
+        let root = env::temp_dir().join(format!("til-first-{}", std::process::id()));
+        for (day, contents) in [
+            ("10-07-2024", "- three\n"),
+            ("10-06-2024", "- one\n- two\n"),
+        ] {
+            fs::create_dir_all(root.join(day)).unwrap();
+            fs::write(root.join(day).join("default.md"), contents).unwrap();
+        }
+
+        let first = |count| {
+            First { count }
+                .bullets_of(notes::days(&root).unwrap())
+                .unwrap()
+        };
+        assert_eq!(
+            first(2),
+            [
+                "10-06-2024/default.md: - one",
+                "10-06-2024/default.md: - two"
+            ]
+        );
+        assert_eq!(first(10).len(), 3);
+        assert!(First { count: 10 }
+            .bullets_of(Vec::new())
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,385 @@
+use serde::{Deserialize, Deserializer};
+use serde_yaml::{Mapping, Value};
+
+use crate::error::Error;
+
+/// The metadata block at the top of a note, delimited by `---` lines.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+pub struct FrontMatter {
+    #[serde(default)]
+    pub title: String,
+
+    #[serde(default, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<String>,
+
+    /// When the note was first written, as an ISO-8601 timestamp.
+    pub created: Option<String>,
+
+    /// When the note was last appended to, as an ISO-8601 timestamp.
+    pub updated: Option<String>,
+
+    /// Whether the note was marked as important with `--pin`.
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// Any other field, kept as is so it survives a rewrite.
+    #[serde(flatten)]
+    pub extra: Mapping,
+}
+
+impl FrontMatter {
+    /// Parses the front matter of a note.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseMetaData` - If the note has no front matter or it isn't valid YAML.
+    pub fn parse(contents: &str) -> crate::error::Result<FrontMatter> {
+        Self::split(contents).map(|(meta, _)| meta)
+    }
+
+    /// Splits a note into its parsed front matter and the body following it.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseMetaData` - If the note has no front matter or it isn't valid YAML.
+    pub fn split(contents: &str) -> crate::error::Result<(FrontMatter, &str)> {
+        let rest = contents
+            .strip_prefix("---\n")
+            .ok_or(Error::CannotParseMetaData)?;
+
+        let (yaml, body) = match rest.strip_prefix("---\n") {
+            Some(body) => ("", body),
+            None => rest
+                .split_once("\n---\n")
+                .ok_or(Error::CannotParseMetaData)?,
+        };
+
+        if yaml.trim().is_empty() {
+            return Ok((FrontMatter::default(), body));
+        }
+
+        let meta = serde_yaml::from_str(yaml).map_err(|_| Error::CannotParseMetaData)?;
+
+        Ok((meta, body))
+    }
+
+    /// Adds the tags which aren't already present, returning whether any was added.
+    pub fn add_tags(&mut self, tags: &[String]) -> bool {
+        let mut added = false;
+
+        for tag in tags {
+            if !self.tags.contains(tag) {
+                self.tags.push(tag.clone());
+                added = true;
+            }
+        }
+
+        added
+    }
+
+    /// Writes the tags in lowercase, dropping the ones differing only by case from
+    /// an earlier one, returning whether the tags changed.
+    pub fn lowercase_tags(&mut self) -> bool {
+        let lowercased = lowercase_tags(&self.tags);
+        let changed = lowercased != self.tags;
+        self.tags = lowercased;
+
+        changed
+    }
+
+    /// Removes a tag, returning whether it was present. With `ignore_case`, every
+    /// spelling of the tag differing only by case is removed.
+    pub fn remove_tag(&mut self, tag: &str, ignore_case: bool) -> bool {
+        let count = self.tags.len();
+        self.tags
+            .retain(|existing| !same_tag(existing, tag, ignore_case));
+
+        self.tags.len() != count
+    }
+
+    /// Renames a tag, keeping its position, returning whether it was present. When
+    /// the new tag is already present the old one is simply removed. With
+    /// `ignore_case`, every spelling of the old tag is renamed.
+    pub fn rename_tag(&mut self, old: &str, new: &str, ignore_case: bool) -> bool {
+        let is_old = |tag: &String| same_tag(tag, old, ignore_case);
+        let Some(index) = self.tags.iter().position(is_old) else {
+            return false;
+        };
+
+        if self
+            .tags
+            .iter()
+            .any(|tag| same_tag(tag, new, ignore_case) && !is_old(tag))
+        {
+            self.tags.retain(|tag| !is_old(tag));
+        } else {
+            self.tags[index] = new.to_owned();
+            let mut position = 0;
+            self.tags.retain(|tag| {
+                let keep = position == index || !is_old(tag);
+                position += 1;
+                keep
+            });
+        }
+
+        true
+    }
+
+    /// Replaces the source tags with a single tag, kept where the first of them or
+    /// the tag itself came, returning whether the tags changed. With `ignore_case`,
+    /// tags are compared ignoring case.
+    pub fn merge_tags(&mut self, sources: &[String], into: &str, ignore_case: bool) -> bool {
+        let mut merged: Vec<String> = Vec::new();
+
+        for tag in &self.tags {
+            let is_source = sources
+                .iter()
+                .any(|source| same_tag(tag, source, ignore_case));
+            let tag = if is_source { into } else { tag };
+            if !merged.iter().any(|kept| same_tag(kept, tag, ignore_case)) {
+                merged.push(tag.to_owned());
+            }
+        }
+
+        let changed = merged != self.tags;
+        self.tags = merged;
+
+        changed
+    }
+
+    /// Renders the front matter block, including its `---` delimiters.
+    ///
+    /// The title and tags always come first, with tags written as a flow list,
+    /// followed by the timestamps when set, `pinned: true` for a pinned note and any
+    /// other field.
+    pub fn render(&self) -> String {
+        let mut block = format!(
+            "---\ntitle: {}\ntags: {}\n",
+            quoted(&self.title),
+            flow_list(&self.tags)
+        );
+
+        if let Some(created) = &self.created {
+            block.push_str(&format!("created: {}\n", created));
+        }
+        if let Some(updated) = &self.updated {
+            block.push_str(&format!("updated: {}\n", updated));
+        }
+        if self.pinned {
+            block.push_str("pinned: true\n");
+        }
+
+        if !self.extra.is_empty() {
+            block.push_str(&serde_yaml::to_string(&self.extra).unwrap_or_default());
+        }
+
+        block.push_str("---\n");
+        block
+    }
+}
+
+/// Returns the tags in lowercase, without the ones differing only by case from an
+/// earlier one.
+pub fn lowercase_tags(tags: &[String]) -> Vec<String> {
+    let mut lowercased: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.to_lowercase()) {
+        if !lowercased.contains(&tag) {
+            lowercased.push(tag);
+        }
+    }
+
+    lowercased
+}
+
+/// Compares two tags, ignoring case when `ignore_case` is set.
+fn same_tag(tag: &str, other: &str, ignore_case: bool) -> bool {
+    if ignore_case {
+        tag.to_lowercase() == other.to_lowercase()
+    } else {
+        tag == other
+    }
+}
+
+/// Quotes a string so it's a valid YAML double-quoted scalar.
+pub fn quoted(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| format!("\"{value}\""))
+}
+
+/// Writes strings as a flow list, e.g. `[rust, "two words"]`.
+pub fn flow_list(values: &[String]) -> String {
+    let values = values
+        .iter()
+        .map(|value| flow_scalar(value))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("[{values}]")
+}
+
+/// Writes a string as a scalar within a flow list, quoting it when needed.
+fn flow_scalar(value: &str) -> String {
+    let is_plain = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_alphanumeric() || "-_./+".contains(c));
+
+    if is_plain {
+        value.to_owned()
+    } else {
+        quoted(value)
+    }
+}
+
+/// Reads tags as strings, accepting numbers and booleans, trimming them and
+/// leaving out empty ones.
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let values = Option::<Vec<Value>>::deserialize(deserializer)?.unwrap_or_default();
+
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match value {
+            Value::String(tag) => Some(tag),
+            Value::Number(tag) => Some(tag.to_string()),
+            Value::Bool(tag) => Some(tag.to_string()),
+            _ => None,
+        })
+        .map(|tag| tag.trim().to_owned())
+        .filter(|tag| !tag.is_empty())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FrontMatter;
+
+    #[test]
+    fn parses_quoted_tags_with_commas() {
+        let meta = FrontMatter::parse("---\ntitle: \"rust\"\ntags: [\"a, b\", c]\n---\n").unwrap();
+
+        assert_eq!(meta.title, "rust");
+        assert_eq!(meta.tags, ["a, b", "c"]);
+    }
+
+    #[test]
+    fn parses_block_style_lists_and_reordered_fields() {
+        let meta =
+            FrontMatter::parse("---\ntags:\n  - rust\n  - async\ntitle: rust\n---\n").unwrap();
+
+        assert_eq!(meta.title, "rust");
+        assert_eq!(meta.tags, ["rust", "async"]);
+    }
+
+    #[test]
+    fn rejects_missing_front_matter() {
+        assert!(FrontMatter::parse("- note\n").is_err());
+        assert!(FrontMatter::parse("---\ntitle: [\n---\n").is_err());
+    }
+
+    #[test]
+    fn timestamps_round_trip() {
+        let contents = "---\ntitle: \"rust\"\ntags: []\ncreated: 2024-10-07T09:00:00+02:00\nupdated: 2024-10-07T17:30:00+02:00\n---\n";
+        let meta = FrontMatter::parse(contents).unwrap();
+
+        assert_eq!(meta.created.as_deref(), Some("2024-10-07T09:00:00+02:00"));
+        assert_eq!(meta.updated.as_deref(), Some("2024-10-07T17:30:00+02:00"));
+        assert!(meta.extra.is_empty());
+        assert_eq!(meta.render(), contents);
+    }
+
+    #[test]
+    fn renamed_tags_are_not_duplicated() {
+        let mut meta = FrontMatter {
+            tags: vec!["rs".to_string(), "async".to_string(), "rust".to_string()],
+            ..Default::default()
+        };
+
+        assert!(meta.rename_tag("async", "futures", false));
+        assert_eq!(meta.tags, ["rs", "futures", "rust"]);
+        assert!(meta.rename_tag("rs", "rust", false));
+        assert_eq!(meta.tags, ["futures", "rust"]);
+        assert!(!meta.rename_tag("go", "golang", false));
+        assert!(meta.remove_tag("futures", false));
+        assert!(!meta.remove_tag("futures", false));
+        assert_eq!(meta.tags, ["rust"]);
+    }
+
+    #[test]
+    fn tags_can_be_matched_ignoring_case() {
+        let mut meta = FrontMatter {
+            tags: ["RUST", "clap", "rust", "Async"]
+                .map(str::to_owned)
+                .to_vec(),
+            ..FrontMatter::default()
+        };
+
+        assert!(!meta.rename_tag("Rust", "rust2", false));
+        assert!(meta.rename_tag("Rust", "rust2", true));
+        assert_eq!(meta.tags, ["rust2", "clap", "Async"]);
+        assert!(meta.merge_tags(&["CLAP".to_owned()], "Rust2", true));
+        assert_eq!(meta.tags, ["rust2", "Async"]);
+        assert!(!meta.remove_tag("async", false));
+        assert!(meta.remove_tag("async", true));
+        assert_eq!(meta.tags, ["rust2"]);
+    }
+
+    #[test]
+    fn merged_tags_take_the_place_of_the_first_source() {
+        let sources = ["Rust", "rustlang", "rust"].map(str::to_owned);
+        let mut meta = FrontMatter {
+            tags: ["async", "rustlang", "clap", "Rust"]
+                .map(str::to_owned)
+                .to_vec(),
+            ..FrontMatter::default()
+        };
+
+        assert!(meta.merge_tags(&sources, "rust", false));
+        assert_eq!(meta.tags, ["async", "rust", "clap"]);
+        assert!(!meta.merge_tags(&sources, "rust", false));
+    }
+
+    #[test]
+    fn only_pinned_notes_render_the_flag() {
+        let (mut meta, _) =
+            FrontMatter::split("---\ntitle: \"rust\"\ntags: []\npinned: false\n---\n").unwrap();
+        assert!(!meta.pinned);
+        assert_eq!(meta.render(), "---\ntitle: \"rust\"\ntags: []\n---\n");
+
+        meta.pinned = true;
+        assert_eq!(
+            meta.render(),
+            "---\ntitle: \"rust\"\ntags: []\npinned: true\n---\n"
+        );
+        assert_eq!(FrontMatter::parse(&meta.render()).unwrap(), meta);
+    }
+
+    #[test]
+    fn lowercased_tags_differing_by_case_are_merged() {
+        let mut meta = FrontMatter {
+            tags: ["Rust", "async", "rust", "RUST", "Tokio"]
+                .map(str::to_owned)
+                .to_vec(),
+            ..FrontMatter::default()
+        };
+
+        assert!(meta.lowercase_tags());
+        assert_eq!(meta.tags, ["rust", "async", "tokio"]);
+        assert!(!meta.lowercase_tags());
+    }
+
+    #[test]
+    fn render_keeps_unknown_fields() {
+        let contents =
+            "---\ntitle: \"rust\"\ntags: [a]\nsource: https://example.com\n---\n\n- note\n";
+        let (mut meta, body) = FrontMatter::split(contents).unwrap();
+
+        assert!(meta.add_tags(&["a".to_string(), "b, c".to_string()]));
+        assert_eq!(
+            format!("{}{}", meta.render(), body),
+            "---\ntitle: \"rust\"\ntags: [a, \"b, c\"]\nsource: https://example.com\n---\n\n- note\n"
+        );
+        assert_eq!(FrontMatter::parse(&meta.render()).unwrap(), meta);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{path::Path, process};
+
+use crate::error::Error;
+
+/// Commits every change within a directory, if it's part of a git repository.
+///
+/// Only changes within the directory are committed, anything else staged in the
+/// repository is left alone. When the directory isn't part of a git repository,
+/// nothing happens, and a warning is printed with a `verbose` level above zero.
+///
+/// ## Errors
+///
+/// * `Error::GitCommand` - If git cannot be run or any git command fails.
+pub fn commit(directory: &Path, message: &str, verbose: u8) -> crate::error::Result<()> {
+    if !is_repository(directory) {
+        if verbose > 0 {
+            eprintln!(
+                "warning: {} is not a git repository, nothing was committed",
+                directory.display()
+            );
+        }
+
+        return Ok(());
+    }
+
+    run(directory, &["add", "--all", "--", "."])?;
+    run(
+        directory,
+        &["commit", "--quiet", "--message", message, "--", "."],
+    )
+}
+
+/// Turns a directory into a git repository with an initial commit, returning
+/// whether one was created. A directory already within a repository is left alone.
+///
+/// ## Errors
+///
+/// * `Error::GitCommand` - If git cannot be run or any git command fails.
+pub fn init(directory: &Path) -> crate::error::Result<bool> {
+    if is_repository(directory) {
+        return Ok(false);
+    }
+
+    run(directory, &["init", "--quiet"])?;
+    run(directory, &["add", "--all"])?;
+    run(
+        directory,
+        &[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "--message",
+            "til: init",
+        ],
+    )?;
+
+    Ok(true)
+}
+
+/// Pulls changes from the remote of the repository a directory is within, rebasing
+/// any local commit on top of them, then pushes the result.
+///
+/// ## Errors
+///
+/// * `Error::NotARepository` - If the directory isn't part of a git repository.
+/// * `Error::MergeConflict` - If the local and remote changes conflict.
+/// * `Error::GitCommand` - If git cannot be run or any git command fails.
+pub fn sync(directory: &Path) -> crate::error::Result<()> {
+    if !is_repository(directory) {
+        return Err(Error::NotARepository(directory.to_owned()));
+    }
+
+    if let Err(err) = run(directory, &["pull", "--rebase", "--quiet"]) {
+        return Err(if has_conflicts(directory) {
+            Error::MergeConflict(directory.to_owned())
+        } else {
+            err
+        });
+    }
+
+    run(directory, &["push", "--quiet"])
+}
+
+/// Checks whether a directory has unmerged files, e.g. after a failed rebase.
+fn has_conflicts(directory: &Path) -> bool {
+    process::Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .output()
+        .is_ok_and(|output| !output.stdout.is_empty())
+}
+
+/// Checks whether a directory is within the work tree of a git repository.
+fn is_repository(directory: &Path) -> bool {
+    process::Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Runs a git command within a directory.
+///
+/// ## Errors
+///
+/// * `Error::GitCommand` - If git cannot be run or exits unsuccessfully.
+fn run(directory: &Path, args: &[&str]) -> crate::error::Result<()> {
+    let command = format!("git {}", args.join(" "));
+    let output = process::Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(|err| Error::GitCommand(command.clone(), err.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_owned();
+        return Err(Error::GitCommand(command, stderr));
+    }
+
+    Ok(())
+}
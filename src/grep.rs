@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use clap::Args;
+use rayon::prelude::*;
+use regex::Regex;
+
+use crate::{error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Grep {
+    /// Regular expression to match against each bullet
+    pub pattern: String,
+
+    /// Print the number of matching bullets per note instead of the bullets
+    #[clap(short, long)]
+    pub count: bool,
+}
+
+impl Grep {
+    /// Matches every bullet of every note against the pattern.
+    ///
+    /// Each match is formatted as `MM-DD-YYYY/title.md: - bullet`, or as
+    /// `MM-DD-YYYY/title.md: N` with `--count`, ordered chronologically. The front
+    /// matter of a note is never searched. Notes are read on a single thread, so an
+    /// encrypted store asks for its passphrase once, then matched in parallel.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidPattern` - If the pattern isn't a valid regular expression.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::NoMatchesFound` - If no bullet matches the pattern.
+    pub fn matches(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let regex = Regex::new(&self.pattern)
+            .map_err(|err| Error::InvalidPattern(self.pattern.clone(), err.to_string()))?;
+        let notes = notes::named_notes(root_dir)?
+            .into_iter()
+            .map(|(name, path)| Ok((name, notes::read(&path)?)))
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        let matches = notes
+            .par_iter()
+            .map(|(name, contents)| {
+                let bullets: Vec<String> = notes::bullets(contents)
+                    .into_iter()
+                    .filter(|bullet| regex.is_match(bullet))
+                    .collect();
+
+                if bullets.is_empty() {
+                    Vec::new()
+                } else if self.count {
+                    vec![format!("{name}: {}", bullets.len())]
+                } else {
+                    bullets
+                        .iter()
+                        .map(|bullet| format!("{name}: - {bullet}"))
+                        .collect()
+                }
+            })
+            .collect::<Vec<_>>()
+            .concat();
+
+        if matches.is_empty() {
+            return Err(Error::NoMatchesFound(self.pattern.clone()));
+        }
+
+        Ok(matches)
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::LazyLock,
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use clap::Args;
+use regex::Regex;
+use serde_yaml::Value;
+
+use crate::{
+    config::Config, crypto, date::parse_date, entry::iso_timestamp, error::Error,
+    front_matter::FrontMatter, index::Index, notes,
+};
+
+/// A date within a file name, e.g. "2024-08-18-rust" or "rust 08-18-2024".
+static NAMED_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\d{4}-\d{2}-\d{2}|\d{2}-\d{2}-\d{4}").unwrap());
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Import {
+    /// The directory of markdown files to import, or a single file
+    pub path: PathBuf,
+}
+
+/// Why a file was left out of the import.
+#[derive(Debug, PartialEq)]
+enum Skipped {
+    Unreadable,
+    InvalidFrontMatter,
+    Empty,
+    Exists(String),
+    InvalidTitle(String),
+}
+
+impl Import {
+    /// Imports every markdown file within the path, returning a line per imported
+    /// or skipped file followed by a summary. With `dry_run`, nothing is written.
+    ///
+    /// The date of a file is read from a `date:` field of its front matter, from a
+    /// date within its name such as "2024-08-18-rust.md", or from its modification
+    /// time, in that order. Its title is read from a `title:` field, or from its name
+    /// without the date. Tags and any other field of the front matter are kept, and
+    /// front matter is generated for files without it.
+    ///
+    /// Hidden files and directories are left out, and files which are unreadable,
+    /// empty, or would replace an existing note are skipped. Imported notes are
+    /// encrypted when `encrypt` is set in the config.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If the path or a directory within it cannot be read.
+    /// * `Error::CannotCreateDir` - If a date directory cannot be created.
+    /// * `Error::CannotWriteToFile` - If a note cannot be written.
+    /// * `Error::NoPassphrase` - If notes are encrypted and no passphrase is available.
+    /// * `Error::WrongPassphrase` - If notes are encrypted with another passphrase.
+    pub fn import(&self, root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+        let config = Config::load()?;
+
+        let mut files = Vec::new();
+        if self.path.is_file() {
+            files.push(self.path.clone());
+        } else {
+            markdown_files(&self.path, &mut files)?;
+        }
+
+        let mut lines = Vec::new();
+        let (mut imported, mut skipped) = (0, 0);
+
+        for file in &files {
+            let name = file
+                .strip_prefix(&self.path)
+                .ok()
+                .filter(|name| !name.as_os_str().is_empty())
+                .unwrap_or(file);
+
+            match prepare(file, config.default_title()) {
+                Ok((date, title, contents)) => {
+                    let directory = notes::day_path(root_dir, date)?;
+                    let path = match notes::note_in(&directory, &title) {
+                        Ok(path) if path.exists() => {
+                            Err(Skipped::Exists(path_name(root_dir, &path)))
+                        }
+                        Ok(path) => Ok(path),
+                        Err(_) => Err(Skipped::InvalidTitle(title)),
+                    };
+
+                    match path {
+                        Ok(path) => {
+                            if !dry_run {
+                                fs::create_dir_all(&directory).map_err(|err| {
+                                    Error::CannotCreateDir(directory.display().to_string(), err)
+                                })?;
+                                let contents = if config.encrypt {
+                                    crypto::seal(&contents, root_dir)?
+                                } else {
+                                    contents
+                                };
+                                notes::write_atomically(&path, &contents)?;
+                                let _ = Index::record(root_dir, &path);
+                            }
+
+                            let verb = if dry_run { "would import" } else { "imported" };
+                            lines.push(format!(
+                                "{verb} {} to {}",
+                                name.display(),
+                                path_name(root_dir, &path)
+                            ));
+                            imported += 1;
+                        }
+                        Err(reason) => {
+                            lines.push(skipped_line(name, &reason));
+                            skipped += 1;
+                        }
+                    }
+                }
+                Err(reason) => {
+                    lines.push(skipped_line(name, &reason));
+                    skipped += 1;
+                }
+            }
+        }
+
+        let verb = if dry_run { "would import" } else { "imported" };
+        lines.push(format!("{verb} {imported} file(s), skipped {skipped}"));
+
+        Ok(lines)
+    }
+}
+
+/// Collects the markdown files within a directory and its subdirectories, sorted by
+/// path and leaving out hidden entries.
+fn markdown_files(directory: &Path, files: &mut Vec<PathBuf>) -> crate::error::Result<()> {
+    let entries = fs::read_dir(directory)
+        .map_err(|err| Error::CannotReadFile(directory.to_path_buf(), err))?;
+
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            !path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    for path in paths {
+        if path.is_dir() {
+            markdown_files(&path, files)?;
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a file to import, returning the date and title of the note along with
+/// the contents to write to it.
+fn prepare(file: &Path, default_title: &str) -> Result<(NaiveDate, String, String), Skipped> {
+    let contents = fs::read_to_string(file)
+        .map_err(|_| Skipped::Unreadable)?
+        .replace("\r\n", "\n");
+
+    let (mut meta, body) = if contents.starts_with("---\n") {
+        FrontMatter::split(&contents).map_err(|_| Skipped::InvalidFrontMatter)?
+    } else {
+        (FrontMatter::default(), contents.as_str())
+    };
+    let body = body.trim();
+    if body.is_empty() {
+        return Err(Skipped::Empty);
+    }
+
+    let stem = file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (named_date, named_title) = split_date(&stem);
+
+    let modified = fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .map(DateTime::<Local>::from)
+        .unwrap_or_else(|_| Local::now());
+    let date = meta
+        .extra
+        .get("date")
+        .and_then(date_field)
+        .or(named_date)
+        .unwrap_or(modified.date_naive());
+
+    if meta.title.trim().is_empty() {
+        meta.title = if named_title.is_empty() {
+            default_title.to_owned()
+        } else {
+            named_title
+        };
+    }
+    let title = meta.title.trim().to_owned();
+    if meta.created.is_none() {
+        meta.created = Some(iso_timestamp(modified));
+    }
+    if meta.updated.is_none() {
+        meta.updated = meta.created.clone();
+    }
+
+    Ok((date, title, format!("{}\n{body}\n", meta.render())))
+}
+
+/// Returns the date within a file name, if any, along with what is left of the name
+/// once it is removed.
+fn split_date(stem: &str) -> (Option<NaiveDate>, String) {
+    let Some(found) = NAMED_DATE
+        .find_iter(stem)
+        .find(|found| parse_date(found.as_str()).is_ok())
+    else {
+        return (None, stem.trim().to_owned());
+    };
+
+    let rest = format!("{} {}", &stem[..found.start()], &stem[found.end()..]);
+    let rest = rest
+        .split(|c: char| c.is_whitespace() || c == '-' || c == '_')
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    (parse_date(found.as_str()).ok(), rest)
+}
+
+/// Reads the `date:` field of some front matter, keeping only the date of a full
+/// timestamp such as "2024-08-18T09:00:00+02:00".
+fn date_field(value: &Value) -> Option<NaiveDate> {
+    let value = value.as_str()?.trim();
+    let date = value.split(['T', ' ']).next().unwrap_or(value);
+    parse_date(date).ok()
+}
+
+fn path_name(root_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(root_dir)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn skipped_line(name: &Path, reason: &Skipped) -> String {
+    let reason = match reason {
+        Skipped::Unreadable => "cannot be read".to_owned(),
+        Skipped::InvalidFrontMatter => "its front matter cannot be parsed".to_owned(),
+        Skipped::Empty => "it is empty".to_owned(),
+        Skipped::Exists(note) => format!("{note} already exists"),
+        Skipped::InvalidTitle(title) => format!("\"{title}\" cannot be used as a title"),
+    };
+
+    format!("skipped {}: {reason}", name.display())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_yaml::Value;
+
+    use super::{date_field, split_date};
+
+    #[test]
+    fn dates_are_taken_out_of_file_names() {
+        let date = NaiveDate::from_ymd_opt(2024, 8, 18);
+
+        assert_eq!(
+            split_date("2024-08-18-rust_async"),
+            (date, "rust async".to_owned())
+        );
+        assert_eq!(
+            split_date("lifetimes 08-18-2024"),
+            (date, "lifetimes".to_owned())
+        );
+        assert_eq!(split_date("2024-08-18"), (date, String::new()));
+        assert_eq!(
+            split_date("9999-99-99 notes"),
+            (None, "9999-99-99 notes".to_owned())
+        );
+    }
+
+    #[test]
+    fn date_fields_accept_timestamps() {
+        let date = NaiveDate::from_ymd_opt(2024, 8, 18);
+
+        assert_eq!(date_field(&Value::from("2024-08-18")), date);
+        assert_eq!(date_field(&Value::from("2024-08-18T09:00:00+02:00")), date);
+        assert_eq!(date_field(&Value::from("2024-08-18 09:00")), date);
+        assert_eq!(date_field(&Value::from(20240818)), None);
+    }
+}
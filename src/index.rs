@@ -0,0 +1,369 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::DateFormat,
+    crypto,
+    error::Error,
+    find_home_dir,
+    notes::{self, Day, Note},
+    stats::count_words,
+};
+
+const INDEX_FILE: &str = "index";
+const VERSION: u32 = 3;
+
+/// What every note holds, saved next to the config so that commands reading every
+/// note don't need to open each of them.
+///
+/// Notes edited outside of `til` are detected by their modification time and size,
+/// and re-read whenever the index is loaded.
+///
+/// Encrypted notes are never decrypted to be indexed, so that the index doesn't
+/// keep what encryption protects. Only their front matter, which is stored in the
+/// clear, is recorded, and their bullets are read with [`IndexedNote::opened`]
+/// when needed.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Index {
+    version: u32,
+    root: PathBuf,
+    pub notes: Vec<IndexedNote>,
+}
+
+/// A note as recorded in the index.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct IndexedNote {
+    pub date: NaiveDate,
+    /// The name of the note relative to the root directory, e.g. "08-18-2024/default.md".
+    pub name: String,
+    pub path: PathBuf,
+    pub title: String,
+    pub tags: Vec<String>,
+    pub bullets: Vec<String>,
+    pub words: usize,
+    pub pinned: bool,
+    /// Whether the body of the note is encrypted, in which case it has no bullets or
+    /// words recorded.
+    pub sealed: bool,
+    modified: u128,
+    size: u64,
+    hash: u64,
+}
+
+impl Index {
+    /// Loads the saved index, re-reading every note added, edited or removed since it
+    /// was saved. A missing, unreadable or outdated index is rebuilt from every note.
+    ///
+    /// The refreshed index is saved again when it changed, but failing to save it
+    /// doesn't keep the notes from being read.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    pub fn load(root_dir: &Path) -> crate::error::Result<Self> {
+        let path = index_path(root_dir)?;
+        let saved = Index::saved(&path, root_dir).unwrap_or_default();
+
+        let (index, changed) = saved.refreshed(root_dir.to_path_buf())?;
+        if changed {
+            let _ = index.save(&path);
+        }
+
+        Ok(index)
+    }
+
+    /// Rebuilds the index from every note, ignoring the saved one.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotCreateDir` - If the home directory cannot be created.
+    /// * `Error::CannotWriteToFile` - If the index cannot be saved.
+    pub fn rebuild(root_dir: &Path) -> crate::error::Result<Self> {
+        let path = index_path(root_dir)?;
+        let (index, _) = Index::default().refreshed(root_dir.to_path_buf())?;
+        index.save(&path)?;
+
+        Ok(index)
+    }
+
+    /// Records a note which was just written in the saved index. Nothing is recorded
+    /// without a saved index, as one is built the next time it is loaded.
+    ///
+    /// Failing to record a note only leaves the index to catch up the next time it
+    /// is loaded, so callers which already wrote the note shouldn't fail because of it.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::CannotWriteToFile` - If the index cannot be saved.
+    pub fn record(root_dir: &Path, path: &Path) -> crate::error::Result<()> {
+        let index_path = index_path(root_dir)?;
+        let Some(mut index) = Index::saved(&index_path, root_dir) else {
+            return Ok(());
+        };
+        let Some(day) = day_of(root_dir, path) else {
+            return Ok(());
+        };
+
+        let note = IndexedNote::read(&day, path)?;
+        match index.notes.iter_mut().find(|indexed| indexed.path == path) {
+            Some(indexed) => *indexed = note,
+            None => {
+                index.notes.push(note);
+                index
+                    .notes
+                    .sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.path.cmp(&b.path)));
+            }
+        }
+
+        index.save(&index_path)
+    }
+
+    /// Drops a note which was just removed or moved elsewhere from the saved index.
+    /// Like [`Index::record`], failing to do so only leaves the index to catch up
+    /// the next time it is loaded.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotWriteToFile` - If the index cannot be saved.
+    pub fn forget(root_dir: &Path, path: &Path) -> crate::error::Result<()> {
+        let index_path = index_path(root_dir)?;
+        let Some(mut index) = Index::saved(&index_path, root_dir) else {
+            return Ok(());
+        };
+
+        let count = index.notes.len();
+        index.notes.retain(|indexed| indexed.path != path);
+        if index.notes.len() == count {
+            return Ok(());
+        }
+
+        index.save(&index_path)
+    }
+
+    /// Reads the saved index, unless it is missing, unreadable, written by another
+    /// version or for another root directory.
+    fn saved(path: &Path, root_dir: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(path).ok()?;
+        serde_json::from_str::<Index>(&contents)
+            .ok()
+            .filter(|index| index.version == VERSION && index.root == root_dir)
+    }
+
+    /// Brings the index up to date with the notes within the root directory, returning
+    /// it along with whether anything changed.
+    ///
+    /// Notes whose modification time and size are unchanged are kept as they are,
+    /// and notes whose contents hash the same are only given their new modification
+    /// time.
+    fn refreshed(self, root_dir: PathBuf) -> crate::error::Result<(Self, bool)> {
+        let mut changed = self.root != root_dir || self.version != VERSION;
+        let mut previous = self
+            .notes
+            .into_iter()
+            .map(|note| (note.path.clone(), note))
+            .collect::<HashMap<_, _>>();
+
+        let mut indexed = Vec::new();
+        for day in notes::days(&root_dir)? {
+            for path in notes::notes_in(&day.path)? {
+                let (modified, size) = file_stamp(&path)?;
+
+                let note = match previous.remove(&path) {
+                    Some(note) if note.modified == modified && note.size == size => note,
+                    Some(note) => {
+                        changed = true;
+                        let contents = read(&path)?;
+
+                        if note.hash == hash(&contents) {
+                            IndexedNote {
+                                modified,
+                                size,
+                                ..note
+                            }
+                        } else {
+                            IndexedNote::parse(&day, &path, &contents, modified, size)
+                        }
+                    }
+                    None => {
+                        changed = true;
+                        IndexedNote::read(&day, &path)?
+                    }
+                };
+                indexed.push(note);
+            }
+        }
+        changed |= !previous.is_empty();
+
+        let index = Index {
+            version: VERSION,
+            root: root_dir,
+            notes: indexed,
+        };
+
+        Ok((index, changed))
+    }
+
+    /// Saves the index, creating the home directory when needed.
+    fn save(&self, path: &Path) -> crate::error::Result<()> {
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)
+                .map_err(|err| Error::CannotCreateDir(directory.display().to_string(), err))?;
+        }
+
+        let contents = serde_json::to_string(self).map_err(|err| Error::Custom(err.to_string()))?;
+        notes::write_atomically(path, &contents)
+    }
+}
+
+impl IndexedNote {
+    /// Reads a note of a day to record it in the index.
+    fn read(day: &Day, path: &Path) -> crate::error::Result<Self> {
+        let (modified, size) = file_stamp(path)?;
+        let contents = read(path)?;
+
+        Ok(IndexedNote::parse(day, path, &contents, modified, size))
+    }
+
+    /// Decrypts an encrypted note to fill in its bullets and words, leaving other
+    /// notes as they are. Nothing decrypted is saved in the index.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::NoPassphrase` - If no passphrase is available.
+    /// * `Error::CannotDecrypt` - If the note cannot be decrypted.
+    pub fn opened(self) -> crate::error::Result<Self> {
+        if !self.sealed {
+            return Ok(self);
+        }
+
+        let contents = notes::read(&self.path)?;
+        let note = Note::parse(self.date, &self.path, &contents);
+
+        Ok(IndexedNote {
+            bullets: note.bullets,
+            words: count_words(&contents),
+            ..self
+        })
+    }
+
+    fn parse(day: &Day, path: &Path, contents: &str, modified: u128, size: u64) -> Self {
+        let note = Note::parse(day.date, path, contents);
+        let sealed = crypto::is_sealed(contents);
+
+        IndexedNote {
+            date: day.date,
+            name: day.note_name(path),
+            path: path.to_path_buf(),
+            title: note.title,
+            tags: note.tags,
+            bullets: if sealed { Vec::new() } else { note.bullets },
+            words: if sealed { 0 } else { count_words(contents) },
+            pinned: note.pinned,
+            sealed,
+            modified,
+            size,
+            hash: hash(contents),
+        }
+    }
+}
+
+/// Returns where the index of a notes directory is saved, e.g.
+/// `~/.til/index-0123456789abcdef.json`. Every notes directory has an index of its
+/// own, so that using another one, e.g. with `--notes-dir`, doesn't throw away the
+/// index of the usual one.
+fn index_path(root_dir: &Path) -> crate::error::Result<PathBuf> {
+    let home_dir = find_home_dir().ok_or(Error::CannotFindDir("home".to_owned()))?;
+    Ok(home_dir.join(index_file(root_dir)))
+}
+
+fn index_file(root_dir: &Path) -> String {
+    format!(
+        "{INDEX_FILE}-{:016x}.json",
+        hash(&root_dir.to_string_lossy())
+    )
+}
+
+/// Returns the day a note within the root directory belongs to.
+fn day_of(root_dir: &Path, path: &Path) -> Option<Day> {
+    let name = path.strip_prefix(root_dir).ok()?.components().next()?;
+    let name = name.as_os_str().to_str()?;
+    let (_, date) = DateFormat::detect(name)?;
+
+    Some(Day {
+        date,
+        path: root_dir.join(name),
+    })
+}
+
+fn read(path: &Path) -> crate::error::Result<String> {
+    fs::read_to_string(path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))
+}
+
+/// Returns the modification time of a file in nanoseconds since the epoch, along
+/// with its size.
+fn file_stamp(path: &Path) -> crate::error::Result<(u128, u64)> {
+    let metadata =
+        fs::metadata(path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |elapsed| elapsed.as_nanos());
+
+    Ok((modified, metadata.len()))
+}
+
+/// Hashes the contents of a note with 64-bit FNV-1a, which stays the same across
+/// builds unlike the standard library's hasher.
+fn hash(contents: &str) -> u64 {
+    contents.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{day_of, hash, index_file};
+
+    #[test]
+    fn hashes_are_stable() {
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c);
+        assert_ne!(hash("- one\n"), hash("- two\n"));
+    }
+
+    #[test]
+    fn every_notes_directory_has_an_index_of_its_own() {
+        let home = index_file(Path::new("/home/me/.til/notes"));
+
+        assert!(home.starts_with("index-") && home.ends_with(".json"));
+        assert_eq!(home, index_file(Path::new("/home/me/.til/notes")));
+        assert_ne!(home, index_file(Path::new("/tmp/scratch")));
+    }
+
+    #[test]
+    fn notes_belong_to_their_date_directory() {
+        let root_dir = Path::new("/notes");
+        let day = day_of(root_dir, Path::new("/notes/08-18-2024/rust/async/tokio.md")).unwrap();
+
+        assert_eq!(day.name(), "08-18-2024");
+        assert!(day_of(root_dir, Path::new("/notes/drafts/tokio.md")).is_none());
+        assert!(day_of(root_dir, Path::new("/elsewhere/08-18-2024/tokio.md")).is_none());
+    }
+}
@@ -0,0 +1,64 @@
+use std::{fs, path::Path};
+
+use clap::Args;
+
+use crate::{config, error::Error, git};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Init {
+    /// Also turn the notes directory into a git repository
+    #[clap(long)]
+    pub git: bool,
+}
+
+impl Init {
+    /// Creates the notes directory and a default config file, along with a git
+    /// repository with `--git`, returning a line describing each of them.
+    ///
+    /// Anything which already exists is left untouched, so running it again is safe.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindDir` - If the home directory cannot be found.
+    /// * `Error::CannotCreateDir` - If the notes directory cannot be created.
+    /// * `Error::CannotWriteToFile` - If the config file cannot be written.
+    /// * `Error::GitCommand` - If the git repository cannot be created.
+    pub fn scaffold(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let config_path = config::config_path()?;
+        let mut lines = Vec::new();
+
+        let status = if root_dir.exists() {
+            "exists"
+        } else {
+            "created"
+        };
+        fs::create_dir_all(root_dir)
+            .map_err(|err| Error::CannotCreateDir(root_dir.display().to_string(), err))?;
+        lines.push(format!("notes   {} ({status})", root_dir.display()));
+
+        let status = if config_path.exists() {
+            "exists"
+        } else {
+            if let Some(directory) = config_path.parent() {
+                fs::create_dir_all(directory)
+                    .map_err(|err| Error::CannotCreateDir(directory.display().to_string(), err))?;
+            }
+            fs::write(&config_path, config::TEMPLATE)
+                .map_err(|err| Error::CannotWriteToFile(config_path.clone(), err))?;
+            "created"
+        };
+        lines.push(format!("config  {} ({status})", config_path.display()));
+
+        if self.git {
+            let status = if git::init(root_dir)? {
+                "created"
+            } else {
+                "exists"
+            };
+            lines.push(format!("git     {} ({status})", root_dir.display()));
+        }
+
+        Ok(lines)
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::{error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Last {
+    /// Number of bullets to show
+    #[clap(default_value_t = 10)]
+    pub count: usize,
+}
+
+impl Last {
+    /// Collects the most recent bullets, newest first, formatted as
+    /// `MM-DD-YYYY/title.md: - bullet`.
+    ///
+    /// Dates are read from the most recent one backwards, and no more notes are read
+    /// once enough bullets are collected.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::NoNotesFound` - If there is no bullet at all.
+    pub fn bullets(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let mut bullets = Vec::new();
+
+        'days: for day in notes::days(root_dir)?.iter().rev() {
+            for path in notes::notes_in(&day.path)? {
+                let contents = notes::read(&path)?;
+                let name = day.note_name(&path);
+
+                for bullet in notes::bullets(&contents).iter().rev() {
+                    if bullets.len() == self.count {
+                        break 'days;
+                    }
+                    bullets.push(format!("{name}: - {bullet}"));
+                }
+            }
+        }
+
+        if bullets.is_empty() && self.count > 0 {
+            return Err(Error::NoNotesFound("any date".to_owned()));
+        }
+
+        Ok(bullets)
+    }
+}
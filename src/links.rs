@@ -0,0 +1,96 @@
+use std::{path::Path, sync::LazyLock};
+
+use clap::Args;
+use regex::Regex;
+
+use crate::{
+    error::Error,
+    notes::{self, body},
+};
+
+/// A `[[title]]` or `[[title|label]]` link to another note.
+static WIKILINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[([^\[\]|]+)(?:\|[^\[\]]*)?\]\]").unwrap());
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Links {
+    /// The title of the note to find the backlinks of
+    #[clap(short, long)]
+    pub title: String,
+}
+
+impl Links {
+    /// Lists every note whose body links to the title with `[[title]]`, ordered
+    /// chronologically.
+    ///
+    /// Titles are compared ignoring case and runs of whitespace, so `[[Rust  Async]]`
+    /// links to a note titled "rust async".
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::NoMatchesFound` - If no note links to the title.
+    pub fn backlinks(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let title = normalize(&self.title);
+        let mut backlinks = Vec::new();
+
+        for day in notes::days(root_dir)? {
+            for path in notes::notes_in(&day.path)? {
+                let contents = notes::read(&path)?;
+
+                if parse_wikilinks(body(&contents))
+                    .iter()
+                    .any(|link| normalize(link) == title)
+                {
+                    backlinks.push(day.note_name(&path));
+                }
+            }
+        }
+
+        if backlinks.is_empty() {
+            return Err(Error::NoMatchesFound(format!("[[{}]]", self.title)));
+        }
+
+        Ok(backlinks)
+    }
+}
+
+/// Returns the titles linked to with `[[title]]` within some text, in order of
+/// appearance and trimmed. The label of a `[[title|label]]` link is left out.
+pub fn parse_wikilinks(body: &str) -> Vec<String> {
+    WIKILINK
+        .captures_iter(body)
+        .map(|captures| captures[1].trim().to_owned())
+        .filter(|title| !title.is_empty())
+        .collect()
+}
+
+fn normalize(title: &str) -> String {
+    title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{normalize, parse_wikilinks};
+
+    #[test]
+    fn wikilinks_are_parsed_with_spaces_and_labels() {
+        assert_eq!(
+            parse_wikilinks("- see [[rust async]] and [[ Pinning | why pin ]]\n- [[]] [not]"),
+            ["rust async", "Pinning"]
+        );
+        assert!(parse_wikilinks("- no links [here]").is_empty());
+    }
+
+    #[test]
+    fn titles_are_compared_ignoring_case_and_spacing() {
+        assert_eq!(normalize("Rust  Async"), normalize("rust async"));
+        assert_ne!(normalize("rust-async"), normalize("rust async"));
+    }
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use clap::Args;
+
+use crate::{date, notes, page::Page};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct List {
+    /// Show the most recent dates first
+    #[clap(short, long)]
+    pub reverse: bool,
+
+    #[clap(flatten)]
+    pub page: Page,
+}
+
+impl List {
+    /// Lists every date with notes alongside the number of notes it holds,
+    /// sorted chronologically. `--limit` and `--skip` apply to the number of dates.
+    /// Dates are shown in the display format when one is set.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If the notes directory or a date directory cannot be read.
+    pub fn days(&self, root_dir: &Path) -> crate::error::Result<Vec<String>> {
+        let mut days = notes::days(root_dir)?;
+
+        if self.reverse {
+            days.reverse();
+        }
+
+        self.page
+            .apply(days)
+            .iter()
+            .map(|day| {
+                let count = notes::notes_in(&day.path)?.len();
+                let noun = if count == 1 { "note" } else { "notes" };
+                Ok(format!(
+                    "{}  {count} {noun}",
+                    date::display(day.date, &day.name())
+                ))
+            })
+            .collect()
+    }
+}
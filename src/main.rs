@@ -1,29 +1,191 @@
+mod amend;
+mod archive;
+mod calendar;
+mod config;
+mod count;
+mod crypto;
+mod date;
+mod dedupe_dates;
+mod destination;
+mod diff;
+mod doctor;
+mod edit;
+mod editor;
 mod entry;
 mod error;
+mod export;
+mod find;
+mod first;
+mod front_matter;
+mod git;
+mod grep;
+mod import;
+mod index;
+mod init;
+mod last;
+mod links;
+mod list;
+mod merge_tags;
+mod migrate_dates;
+mod mv;
+mod note_path;
+mod notes;
+mod open;
+mod page;
+mod pinned;
+mod random;
+mod rename_tag;
+mod review;
+mod rm;
 mod search;
+mod stats;
+mod style;
+mod tag_cloud;
+mod tags;
+mod template;
+mod untag;
+mod watch;
+mod weekly;
 
-use clap::{Parser, Subcommand};
+use amend::Amend;
+use archive::Archive;
+use calendar::Calendar;
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use config::{Config, ConfigCommand};
+use count::Count;
+use destination::Destination;
+use diff::Diff;
+use doctor::Doctor;
+use edit::Edit;
 use entry::Entry;
 use error::Error;
-use regex::Regex;
+use export::Export;
+use find::Find;
+use first::First;
+use grep::Grep;
+use import::Import;
+use index::Index;
+use init::Init;
+use last::Last;
+use links::Links;
+use list::List;
+use merge_tags::MergeTags;
+use migrate_dates::MigrateDates;
+use mv::Mv;
+use note_path::NotePath;
+use open::Open;
+use random::Random;
+use rename_tag::RenameTag;
+use review::Review;
+use rm::Rm;
 use search::Search;
-use std::path::{Path, PathBuf};
+use stats::Stats;
+use std::{
+    env,
+    error::Error as _,
+    io,
+    path::{self, Path, PathBuf},
+};
+use tag_cloud::TagCloud;
+use tags::Tags;
+use untag::Untag;
+use watch::Watch;
+use weekly::Weekly;
 
-const PATH_FROM_ROOT: &str = ".til/notes";
+const PATH_FROM_ROOT: &str = ".til";
+const NOTES_DIR: &str = "notes";
+const HOME_VAR: &str = "TIL_HOME";
+const EXIT_CODES: &str = "\
+Exit codes:
+  0  Success
+  1  Any other failure, e.g. the editor or git failed
+  2  Invalid arguments
+  3  Not found, e.g. no notes for a date or no matches
+  4  A file or directory cannot be read, written or created
+  5  Invalid input, e.g. a date, title or config that cannot be parsed";
 
-fn find_root_dir() -> Option<PathBuf> {
-    Some(Path::new(&dirs::home_dir()?).join(PATH_FROM_ROOT))
+/// Finds the directory holding the config and, by default, the notes.
+///
+/// This is `$TIL_HOME` when the `TIL_HOME` environment variable is set, and
+/// `~/.til` otherwise.
+fn find_home_dir() -> Option<PathBuf> {
+    match env::var_os(HOME_VAR).filter(|home| !home.is_empty()) {
+        Some(home) => Some(PathBuf::from(home)),
+        None => Some(Path::new(&dirs::home_dir()?).join(PATH_FROM_ROOT)),
+    }
+}
+
+/// Finds the directory where notes are stored, which is handed to the command run
+/// so that every part of it reads and writes the same notes.
+///
+/// In order of precedence, notes are stored in the directory passed with
+/// `--notes-dir`, resolved against the current directory when relative, in
+/// `$TIL_HOME/notes` when the `TIL_HOME` environment variable is set, in the `root`
+/// directory of the config when set, and in `~/.til/notes` otherwise.
+///
+/// ## Errors
+///
+/// * `Error::CannotFindDir` - If the directory cannot be found or made absolute.
+/// * `Error::CannotParseConfig` - If the config cannot be parsed.
+fn find_root_dir(notes_dir: Option<&Path>) -> error::Result<PathBuf> {
+    if let Some(path) = notes_dir {
+        return path::absolute(path).map_err(|_| Error::CannotFindDir(path.display().to_string()));
+    }
+
+    let home_dir = find_home_dir().ok_or(Error::CannotFindDir("root".to_owned()))?;
+
+    if env::var_os(HOME_VAR).is_some_and(|home| !home.is_empty()) {
+        return Ok(home_dir.join(NOTES_DIR));
+    }
+
+    match Config::load()?.root {
+        Some(root) => match (root.strip_prefix("~"), dirs::home_dir()) {
+            (Ok(relative), Some(user_home)) => Ok(user_home.join(relative)),
+            _ => Ok(root),
+        },
+        None => Ok(home_dir.join(NOTES_DIR)),
+    }
 }
 
 #[derive(Parser, Debug)]
-#[command(name = "til", version, about = "✨ 'today i learned' is used to keep track of the important sh%t you want to remember ✨", long_about = None, arg_required_else_help = true)]
+#[command(name = "til", version, about = "✨ 'today i learned' is used to keep track of the important sh%t you want to remember ✨", long_about = None, arg_required_else_help = true, after_help = EXIT_CODES)]
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+
+    /// Print more about what is happening, e.g. which file a note was written to
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Print nothing on success, e.g. when scripting
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print what would change instead of writing anything
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Print plain text without colors, which are also left out when NO_COLOR is set
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Display dates in this chrono format, e.g. "%b %-d, %Y", leaving the names of the date directories as they are
+    #[arg(long, global = true, value_name = "FORMAT")]
+    output_date_format: Option<String>,
+
+    /// Store notes in this directory for this run, instead of in TIL_HOME or the configured root
+    #[arg(long, global = true, value_name = "PATH")]
+    notes_dir: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
 enum Command {
+    /// Create the notes directory and a default config file
+    Init {
+        #[clap(flatten)]
+        init: Init,
+    },
     /// Add a new note entry
     Add {
         #[clap(flatten)]
@@ -33,36 +195,381 @@ enum Command {
     Search {
         #[clap(flatten)]
         search: Search,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the most recent bullets, newest first
+    Last {
+        #[clap(flatten)]
+        last: Last,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the earliest bullets, oldest first
+    First {
+        #[clap(flatten)]
+        first: First,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the bullets only one of two dates has
+    Diff {
+        #[clap(flatten)]
+        diff: Diff,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print today's notes, the same as `search` without a date
+    Today {
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print today's note and keep printing the bullets appended to it
+    Watch {
+        #[clap(flatten)]
+        watch: Watch,
+    },
+    /// Find every note bullet containing some text
+    Find {
+        #[clap(flatten)]
+        find: Find,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Find note bullets matching a regular expression
+    Grep {
+        #[clap(flatten)]
+        grep: Grep,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// List every note linking to a title with [[title]]
+    Links {
+        #[clap(flatten)]
+        links: Links,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// List every date with notes
+    List {
+        #[clap(flatten)]
+        list: List,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// List every tag with the number of notes carrying it
+    Tags {
+        #[clap(flatten)]
+        tags: Tags,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// List the notes marked as important with `--pin`
+    Pinned {
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the tags by frequency with a bar sized by their count
+    TagCloud {
+        #[clap(flatten)]
+        tag_cloud: TagCloud,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Show a calendar of the days with notes
+    Calendar {
+        #[clap(flatten)]
+        calendar: Calendar,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print a random bullet from a past note to review
+    Random {
+        #[clap(flatten)]
+        random: Random,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the note due for review the longest and schedule its next review by how well it was recalled
+    Review {
+        #[clap(flatten)]
+        review: Review,
+    },
+    /// Remove tags from a note
+    Untag {
+        #[clap(flatten)]
+        untag: Untag,
+    },
+    /// Rename a tag across every note
+    RenameTag {
+        #[clap(flatten)]
+        rename_tag: RenameTag,
+    },
+    /// Merge several tags into one across every note
+    MergeTags {
+        #[clap(flatten)]
+        merge_tags: MergeTags,
     },
+    /// Summarize your note activity
+    Stats {
+        #[clap(flatten)]
+        stats: Stats,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print the number of bullets, or of notes carrying a tag
+    Count {
+        #[clap(flatten)]
+        count: Count,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Print a digest of the notes from the past week
+    Weekly {
+        #[clap(flatten)]
+        weekly: Weekly,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Replace the last bullet of a note
+    Amend {
+        #[clap(flatten)]
+        amend: Amend,
+    },
+    /// Open an existing note in $EDITOR
+    Edit {
+        #[clap(flatten)]
+        edit: Edit,
+    },
+    /// Print the path of a note, or of the notes directory, whether or not it exists
+    Path {
+        #[clap(flatten)]
+        path: NotePath,
+    },
+    /// Open the notes directory in the file manager
+    Open {
+        #[clap(flatten)]
+        open: Open,
+    },
+    /// Move a note to another date or title
+    Mv {
+        #[clap(flatten)]
+        mv: Mv,
+    },
+    /// Remove a note
+    Rm {
+        #[clap(flatten)]
+        rm: Rm,
+    },
+    /// Check the front matter of every note, repairing it with --fix
+    Doctor {
+        #[clap(flatten)]
+        doctor: Doctor,
+    },
+    /// Rename the date directories to the configured date format
+    MigrateDates {
+        #[clap(flatten)]
+        migrate_dates: MigrateDates,
+    },
+    /// Merge the directories of the same date, e.g. "1-5-2024" and "01-05-2024"
+    DedupeDates,
+    /// Move the date directories before a date to the archive, out of the way of other commands
+    Archive {
+        #[clap(flatten)]
+        archive: Archive,
+    },
+    /// Rebuild the index of every note from scratch
+    Reindex,
+    /// Import a directory of markdown files as notes
+    Import {
+        #[clap(flatten)]
+        import: Import,
+    },
+    /// Export every note into a single document
+    Export {
+        #[clap(flatten)]
+        export: Export,
+        #[clap(flatten)]
+        destination: Destination,
+    },
+    /// Pull and push the notes with git
+    Sync,
+    /// Inspect the config file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Print a shell completion script
+    Completions {
+        /// The shell to generate completions for
+        shell: Shell,
+    },
+}
+
+fn main() {
+    if let Err(err) = run() {
+        match err.source() {
+            Some(source) => eprintln!("{err}: {source}"),
+            None => eprintln!("{err}"),
+        }
+        std::process::exit(err.exit_code());
+    }
 }
 
-fn main() -> error::Result<()> {
+fn run() -> error::Result<()> {
     let args = Cli::parse();
 
+    if args.no_color {
+        owo_colors::set_override(false);
+    }
+    if let Some(format) = &args.output_date_format {
+        date::set_display_format(format)?;
+    }
+    let root_dir = || find_root_dir(args.notes_dir.as_deref());
+
     match args.command {
         Some(command) => {
+            let scans_notes = matches!(
+                command,
+                Command::Search { .. }
+                    | Command::Today { .. }
+                    | Command::List { .. }
+                    | Command::Stats { .. }
+            );
+            let displays_dates =
+                scans_notes || matches!(command, Command::Weekly { .. } | Command::Review { .. });
+            if displays_dates {
+                date::load_display_format()?;
+            }
+            if scans_notes && args.verbose > 0 {
+                notes::stray_files(&root_dir()?)?
+                    .iter()
+                    .for_each(|name| eprintln!("warning: skipped {name}, which is not a note"));
+            }
+
             match command {
-                Command::Add { entry } => entry.write()?,
-                Command::Search { search } => {
-                    let mut entry = String::default();
-                    if let Some(date) = search.date {
-                        // must use MM-DD-YYYY for date argument
-                        let re = Regex::new(r"^\d{1,2}-\d{1,2}-\d{4}$").unwrap();
-                        if !re.is_match(&date) {
-                            let err = Error::InvalidDateFormat;
-                            eprintln!("{err}");
-                            std::process::exit(1);
-                        }
-
-                        entry = match Search::by_date(date.to_owned()) {
-                            Some(contents) => contents,
-                            None => {
-                                eprintln!("no notes were found from {}", date);
-                                std::process::exit(1);
-                            }
-                        };
+                Command::Init { init } => init
+                    .scaffold(&root_dir()?)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::Add { entry } => {
+                    entry.write(&root_dir()?, args.verbose, args.quiet, args.dry_run)?
+                }
+                Command::Search {
+                    search,
+                    destination,
+                } => destination.with(|out| search.retrieve(&root_dir()?, out))?,
+                Command::Last { last, destination } => {
+                    destination.write_lines(&last.bullets(&root_dir()?)?)?
+                }
+                Command::First { first, destination } => {
+                    let bullets = first.bullets(&root_dir()?)?;
+                    if bullets.is_empty() && !args.quiet {
+                        eprintln!("no notes yet");
                     }
-                    println!("{}", entry.trim())
+                    destination.write_lines(&bullets)?
+                }
+                Command::Today { destination } => {
+                    destination.with(|out| Search::default().retrieve(&root_dir()?, out))?
+                }
+                Command::Diff { diff, destination } => {
+                    destination.write_lines(&diff.lines(&root_dir()?)?)?
+                }
+                Command::Watch { watch } => watch.watch(&root_dir()?, &mut io::stdout().lock())?,
+                Command::Find { find, destination } => {
+                    destination.write_lines(&find.matches(&root_dir()?)?)?
+                }
+                Command::Grep { grep, destination } => {
+                    destination.write_lines(&grep.matches(&root_dir()?)?)?
+                }
+                Command::Links { links, destination } => {
+                    destination.write_lines(&links.backlinks(&root_dir()?)?)?
+                }
+                Command::List { list, destination } => {
+                    destination.write_lines(&list.days(&root_dir()?)?)?
+                }
+                Command::Tags { tags, destination } => {
+                    destination.write_lines(&[tags.counts(&root_dir()?)?])?
+                }
+                Command::Pinned { destination } => {
+                    destination.write_lines(&pinned::pinned(&root_dir()?)?)?
+                }
+                Command::TagCloud {
+                    tag_cloud,
+                    destination,
+                } => destination.write_lines(&[tag_cloud.render(&root_dir()?)?])?,
+                Command::Calendar {
+                    calendar,
+                    destination,
+                } => destination.write_lines(&[calendar.render(&root_dir()?)?])?,
+                Command::Random {
+                    random,
+                    destination,
+                } => destination.write_lines(&[random.pick(&root_dir()?)?])?,
+                Command::Review { review } => review.review(&root_dir()?)?,
+                Command::Untag { untag } => untag.untag(&root_dir()?)?,
+                Command::RenameTag { rename_tag } => rename_tag
+                    .rename(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::MergeTags { merge_tags } => merge_tags
+                    .merge(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::Stats { stats, destination } => {
+                    destination.write_lines(&[stats.summary(&root_dir()?)?])?
+                }
+                Command::Count { count, destination } => {
+                    destination.write_lines(&[count.total(&root_dir()?)?.to_string()])?
+                }
+                Command::Weekly {
+                    weekly,
+                    destination,
+                } => destination.with(|out| weekly.digest(&root_dir()?, out))?,
+                Command::Amend { amend } => amend.amend(&root_dir()?)?,
+                Command::Edit { edit } => edit.open(&root_dir()?)?,
+                Command::Path { path } => println!("{}", path.resolve(&root_dir()?)?.display()),
+                Command::Open { open } => open.launch(&root_dir()?)?,
+                Command::Mv { mv } => mv.relocate(&root_dir()?)?,
+                Command::Rm { rm } => rm.remove(&root_dir()?)?,
+                Command::Doctor { doctor } => doctor
+                    .check(&root_dir()?)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::MigrateDates { migrate_dates } => migrate_dates
+                    .migrate(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::DedupeDates => dedupe_dates::dedupe(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::Archive { archive } => archive
+                    .archive(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::Reindex => println!(
+                    "indexed {} notes",
+                    Index::rebuild(&root_dir()?)?.notes.len()
+                ),
+                Command::Import { import } => import
+                    .import(&root_dir()?, args.dry_run)?
+                    .iter()
+                    .for_each(|line| println!("{line}")),
+                Command::Export {
+                    export,
+                    destination,
+                } => destination.with(|out| export.export(&root_dir()?, out))?,
+                Command::Sync => {
+                    git::sync(&root_dir()?)?;
+                    println!("notes are in sync");
+                }
+                Command::Config { command } => match command {
+                    ConfigCommand::Path => println!("{}", config::config_path()?.display()),
+                },
+                Command::Completions { shell } => {
+                    let mut command = Cli::command();
+                    let name = command.get_name().to_owned();
+                    clap_complete::generate(shell, &mut command, name, &mut io::stdout());
                 }
             };
 
@@ -71,3 +578,28 @@ fn main() -> error::Result<()> {
         None => Err(Error::CannotProcessArgs),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use clap::{CommandFactory, Parser};
+
+    use crate::Cli;
+
+    #[test]
+    fn cli_is_well_formed() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn notes_dir_is_accepted_around_any_command() {
+        for args in [
+            ["til", "--notes-dir", "/tmp/notes", "path", "--dir"],
+            ["til", "path", "--dir", "--notes-dir", "/tmp/notes"],
+        ] {
+            let cli = Cli::parse_from(args);
+            assert_eq!(cli.notes_dir.as_deref(), Some(Path::new("/tmp/notes")));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{fs, path::Path};
+
+use clap::Args;
+
+use crate::{config::Config, error::Error, front_matter::FrontMatter, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct MergeTags {
+    /// The tags to merge, e.g. "Rust rustlang"
+    #[clap(required = true)]
+    pub sources: Vec<String>,
+
+    /// The tag to merge them into, which may be one of them
+    #[clap(long)]
+    pub into: String,
+}
+
+impl MergeTags {
+    /// Merges several tags into one in the front matter of every note carrying any
+    /// of them, returning a line per changed note followed by a summary. With
+    /// `dry_run`, nothing is rewritten.
+    ///
+    /// The merged tag takes the place of the first tag it replaces, and is never
+    /// listed twice. Tags are compared ignoring case with `case_insensitive_tags`
+    /// configured. Notes without front matter are left alone, with a warning.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTag` - If the tag to merge into is empty.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotWriteToFile` - If a note cannot be rewritten.
+    pub fn merge(&self, root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+        let into = self.into.trim();
+        if into.is_empty() {
+            return Err(Error::InvalidTag(self.into.clone()));
+        }
+        let sources = self
+            .sources
+            .iter()
+            .map(|tag| tag.trim().to_owned())
+            .collect::<Vec<_>>();
+
+        let ignore_case = Config::load()?.case_insensitive_tags;
+        let mut lines = Vec::new();
+
+        for day in notes::days(root_dir)? {
+            for path in notes::notes_in(&day.path)? {
+                let contents = fs::read_to_string(&path)
+                    .map_err(|err| Error::CannotReadFile(path.clone(), err))?;
+                if !contents.starts_with("---\n") {
+                    eprintln!(
+                        "warning: {} has no front matter, so it has no tags to merge",
+                        path.display()
+                    );
+                    continue;
+                }
+                let Ok((mut meta, body)) = FrontMatter::split(&contents) else {
+                    continue;
+                };
+
+                if !meta.merge_tags(&sources, into, ignore_case) {
+                    continue;
+                }
+
+                if !dry_run {
+                    notes::write_atomically(&path, &format!("{}{}", meta.render(), body))?;
+                }
+
+                lines.push(day.note_name(&path));
+            }
+        }
+
+        let verb = if dry_run { "would merge" } else { "merged" };
+        let sources = sources
+            .iter()
+            .map(|tag| format!("\"{tag}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        lines.push(format!(
+            "{verb} {sources} into \"{into}\" in {} note(s)",
+            lines.len()
+        ));
+
+        Ok(lines)
+    }
+}
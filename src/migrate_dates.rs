@@ -0,0 +1,81 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::Args;
+
+use crate::{
+    config::{Config, DateFormat},
+    error::Error,
+    notes,
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct MigrateDates {
+    /// The format to rename the date directories to, defaults to the configured one
+    #[clap(long, value_enum)]
+    pub to: Option<DateFormat>,
+}
+
+impl MigrateDates {
+    /// Renames every date directory which isn't named in the target format, returning
+    /// a line per renamed directory followed by a summary.
+    ///
+    /// Nothing is renamed when a directory would end up with the name of another
+    /// one, and with `dry_run` the renames are only listed.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If the notes directory cannot be read.
+    /// * `Error::DateDirExists` - If two directories would end up with the same name.
+    /// * `Error::CannotWriteToFile` - If a directory cannot be renamed.
+    pub fn migrate(&self, root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+        let config = Config::load()?;
+        let format = self.to.unwrap_or(config.date_format);
+
+        let renames = notes::days(root_dir)?
+            .into_iter()
+            .map(|day| {
+                let target = day.path.with_file_name(format.format(day.date));
+                (day.path, target)
+            })
+            .filter(|(source, target)| source != target)
+            .collect::<Vec<(PathBuf, PathBuf)>>();
+
+        let mut targets = HashSet::new();
+        for (_, target) in &renames {
+            if target.exists() || !targets.insert(target) {
+                return Err(Error::DateDirExists(target.clone()));
+            }
+        }
+
+        let mut lines = Vec::new();
+        for (source, target) in &renames {
+            if !dry_run {
+                fs::rename(source, target)
+                    .map_err(|err| Error::CannotWriteToFile(source.clone(), err))?;
+            }
+
+            let verb = if dry_run { "would rename" } else { "renamed" };
+            lines.push(format!(
+                "{verb} {} to {}",
+                source.file_name().unwrap_or_default().to_string_lossy(),
+                target.file_name().unwrap_or_default().to_string_lossy()
+            ));
+        }
+
+        let verb = if dry_run { "would migrate" } else { "migrated" };
+        lines.push(format!("{verb} {} directories", renames.len()));
+        if format != config.date_format {
+            lines.push(format!(
+                "set date_format = \"{}\" in the config to keep writing notes in this format",
+                format.name()
+            ));
+        }
+
+        Ok(lines)
+    }
+}
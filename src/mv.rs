@@ -0,0 +1,125 @@
+use std::{fs, path::Path};
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{
+    config::Config,
+    date::parse_date,
+    error::Error,
+    front_matter::FrontMatter,
+    index::Index,
+    notes::{self, Extension},
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Mv {
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+
+    /// Move the note to this date, defaults to its current date
+    #[clap(long, required_unless_present = "to_title")]
+    pub to_date: Option<String>,
+
+    /// Rename the note to this title, defaults to its current title
+    #[clap(long)]
+    pub to_title: Option<String>,
+
+    /// Overwrite the destination note if it already exists
+    #[clap(short, long)]
+    pub force: bool,
+}
+
+impl Mv {
+    /// Moves a note to another date and/or title. The title in its front matter is
+    /// only updated to match when it is renamed with `--to-title`.
+    ///
+    /// The note keeps its extension, and a note of the destination title with
+    /// another extension is replaced along with it under `--force`. The destination
+    /// date directory is created when needed, and the source date directory is
+    /// removed once it no longer holds any files. The index is updated to match.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If either date cannot be parsed.
+    /// * `Error::InvalidTitle` - If either title cannot be used as a file name.
+    /// * `Error::CannotFindFile` - If the note does not exist.
+    /// * `Error::FileExists` - If the destination exists and `--force` isn't passed.
+    /// * `Error::CannotCreateDir` - If the destination directory cannot be created.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::CannotOpenOrCreatePath` - If the moved note cannot be created.
+    /// * `Error::CannotWriteToFile` - If the moved note cannot be written.
+    /// * `Error::CannotRemoveFile` - If the source note cannot be removed.
+    pub fn relocate(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let config = Config::load()?;
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        let to_date = match &self.to_date {
+            Some(to_date) => parse_date(to_date)?,
+            None => date,
+        };
+        let to_title = self.to_title.as_deref().unwrap_or(title);
+
+        let source = notes::note_path(root_dir, date, title)?;
+        if !source.exists() {
+            return Err(Error::CannotFindFile(source));
+        }
+
+        let to_directory = notes::day_path(root_dir, to_date)?;
+        let destination = notes::note_with_extension(
+            &to_directory,
+            to_title,
+            Extension::of(&source).unwrap_or_default(),
+        )?;
+        if source == destination {
+            return Ok(());
+        }
+
+        let existing = notes::note_in(&to_directory, to_title)?;
+        let replaced = (existing.exists() && existing != source).then_some(existing);
+        if let Some(existing) = &replaced {
+            if !self.force {
+                return Err(Error::FileExists(existing.clone()));
+            }
+        }
+
+        let contents = fs::read_to_string(&source)
+            .map_err(|err| Error::CannotReadFile(source.clone(), err))?;
+        let contents = match (&self.to_title, FrontMatter::split(&contents)) {
+            (Some(to_title), Ok((mut meta, body))) => {
+                meta.title = to_title.trim().to_owned();
+                format!("{}{}", meta.render(), body)
+            }
+            _ => contents,
+        };
+
+        if let Some(directory) = destination.parent() {
+            fs::create_dir_all(directory)
+                .map_err(|err| Error::CannotCreateDir(directory.display().to_string(), err))?;
+        }
+        notes::write_atomically(&destination, &contents)?;
+        let _ = Index::record(root_dir, &destination);
+        fs::remove_file(&source).map_err(|err| Error::CannotRemoveFile(source.clone(), err))?;
+        let _ = Index::forget(root_dir, &source);
+        if let Some(existing) = replaced.filter(|existing| *existing != destination) {
+            fs::remove_file(&existing)
+                .map_err(|err| Error::CannotRemoveFile(existing.clone(), err))?;
+            let _ = Index::forget(root_dir, &existing);
+        }
+
+        if let Some(directory) = source.parent() {
+            notes::remove_dir_if_empty(directory)?;
+        }
+
+        Ok(())
+    }
+}
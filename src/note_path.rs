@@ -0,0 +1,49 @@
+use std::path::{self, Path, PathBuf};
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{config::Config, date::parse_date, error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct NotePath {
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+
+    /// Print the notes directory instead
+    #[clap(long, conflicts_with_all = ["date", "title"])]
+    pub dir: bool,
+}
+
+impl NotePath {
+    /// Resolves the absolute path of a note, whether or not it exists, or of the
+    /// notes directory with `--dir`.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotFindDir` - If the path cannot be made absolute.
+    /// * `Error::InvalidTitle` - If the title cannot be used as a file name.
+    pub fn resolve(&self, root_dir: &Path) -> crate::error::Result<PathBuf> {
+        let path = if self.dir {
+            root_dir.to_path_buf()
+        } else {
+            let date = match &self.date {
+                Some(date) => parse_date(date)?,
+                None => Local::now().date_naive(),
+            };
+            let config = Config::load()?;
+            let title = self.title.as_deref().unwrap_or(config.default_title());
+
+            notes::note_path(root_dir, date, title)?
+        };
+
+        path::absolute(&path).map_err(|_| Error::CannotFindDir(path.display().to_string()))
+    }
+}
@@ -0,0 +1,886 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde_yaml::Mapping;
+
+use crate::{
+    config::{Config, DateFormat},
+    crypto,
+    error::Error,
+    front_matter::FrontMatter,
+};
+
+/// The directory within the notes directory old date directories are moved to.
+const ARCHIVE_DIR: &str = "archive";
+/// What bullets start with unless configured otherwise.
+const DEFAULT_BULLET_PREFIX: &str = "- ";
+/// The markers of Markdown lists, which start bullets whichever prefix is configured.
+const LIST_MARKERS: [&str; 3] = ["- ", "* ", "+ "];
+
+/// What bullets start with for this run.
+static BULLET_PREFIX: OnceLock<String> = OnceLock::new();
+
+/// A directory holding every note written on a single date.
+#[derive(Debug)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub path: PathBuf,
+}
+
+impl Day {
+    /// Returns the name of the day's directory, e.g. "08-18-2024".
+    pub fn name(&self) -> String {
+        self.path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of a note relative to the root directory, e.g.
+    /// "08-18-2024/rust/async/default.md" for a note filed under a topic.
+    pub fn note_name(&self, path: &Path) -> String {
+        let relative = path.strip_prefix(&self.path).unwrap_or(path);
+        format!("{}/{}", self.name(), relative.to_string_lossy())
+    }
+}
+
+/// A note of a day, parsed from its front matter and body.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Note {
+    pub date: NaiveDate,
+    pub title: String,
+    pub tags: Vec<String>,
+    /// The top-level bullets of the body, without their `- ` prefix.
+    pub bullets: Vec<String>,
+    /// Everything following the front matter, bullets included.
+    pub body: String,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+    pub pinned: bool,
+    /// Any other field of the front matter, kept as is.
+    pub extra: Mapping,
+}
+
+impl Note {
+    /// Creates a note holding only the given bullets.
+    pub fn new(date: NaiveDate, title: &str, tags: &[String], bullets: Vec<String>) -> Self {
+        Note {
+            date,
+            title: title.to_owned(),
+            tags: tags.to_vec(),
+            body: bullet_lines(&bullets),
+            bullets,
+            ..Default::default()
+        }
+    }
+
+    /// Reads a note, taking its date from the date directory holding it.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the note isn't within a date directory.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    pub fn from_file(path: &Path) -> crate::error::Result<Self> {
+        let date = path
+            .ancestors()
+            .skip(1)
+            .find_map(|ancestor| DateFormat::detect(ancestor.file_name()?.to_str()?))
+            .map(|(_, date)| date)
+            .ok_or_else(|| Error::CannotParseDate(path.display().to_string()))?;
+        let contents = read(path)?;
+
+        Ok(Note::parse(date, path, &contents))
+    }
+
+    /// Parses the contents of a note of a date.
+    ///
+    /// A note without front matter, or whose front matter cannot be parsed, has no
+    /// tags and is titled after its file name.
+    pub fn parse(date: NaiveDate, path: &Path, contents: &str) -> Self {
+        let (meta, body) = FrontMatter::split(contents)
+            .unwrap_or_else(|_| (FrontMatter::default(), self::body(contents)));
+        let body = body.trim_start_matches('\n');
+
+        Note {
+            date,
+            title: title(path, &meta),
+            tags: meta.tags,
+            bullets: body
+                .lines()
+                .filter_map(strip_bullet)
+                .map(str::to_owned)
+                .collect(),
+            body: body.to_owned(),
+            created: meta.created,
+            updated: meta.updated,
+            pinned: meta.pinned,
+            extra: meta.extra,
+        }
+    }
+
+    /// Renders the note as markdown, with its front matter followed by a blank line
+    /// and its body.
+    pub fn to_markdown(&self) -> String {
+        let meta = FrontMatter {
+            title: self.title.clone(),
+            tags: self.tags.clone(),
+            created: self.created.clone(),
+            updated: self.updated.clone(),
+            pinned: self.pinned,
+            extra: self.extra.clone(),
+        };
+
+        format!("{}\n{}", meta.render(), self.body)
+    }
+}
+
+/// Writes bullets as the lines of a note, e.g. "- bullet\n", starting them with the
+/// configured prefix.
+pub fn bullet_lines(bullets: &[String]) -> String {
+    let prefix = bullet_prefix();
+
+    bullets
+        .iter()
+        .map(|bullet| format!("{prefix}{bullet}\n"))
+        .collect()
+}
+
+/// Sets what bullets start with for this run, taking precedence over the
+/// `bullet_prefix` of the config.
+///
+/// ## Errors
+///
+/// * `Error::InvalidBulletPrefix` - If the prefix is blank or spans several lines.
+pub fn set_bullet_prefix(prefix: &str) -> crate::error::Result<()> {
+    validate_bullet_prefix(prefix)?;
+    let _ = BULLET_PREFIX.set(prefix.to_owned());
+
+    Ok(())
+}
+
+/// Returns what bullets start with, "- " unless configured otherwise.
+pub fn bullet_prefix() -> &'static str {
+    BULLET_PREFIX.get_or_init(|| {
+        Config::load()
+            .ok()
+            .and_then(|config| config.bullet_prefix)
+            .unwrap_or_else(|| DEFAULT_BULLET_PREFIX.to_owned())
+    })
+}
+
+/// Checks that bullets can be told apart by a prefix.
+///
+/// ## Errors
+///
+/// * `Error::InvalidBulletPrefix` - If the prefix is blank or spans several lines.
+pub fn validate_bullet_prefix(prefix: &str) -> crate::error::Result<()> {
+    if prefix.trim().is_empty() || prefix.contains(['\n', '\r']) {
+        return Err(Error::InvalidBulletPrefix(prefix.to_owned()));
+    }
+
+    Ok(())
+}
+
+/// Returns a line without its bullet prefix, or `None` when it isn't a bullet.
+///
+/// Lines starting with "- ", "* " or "+ " are bullets whichever prefix is
+/// configured, so that the notes written before changing it are still read.
+pub fn strip_bullet(line: &str) -> Option<&str> {
+    strip_bullet_with(line, bullet_prefix())
+}
+
+fn strip_bullet_with<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    line.strip_prefix(prefix).or_else(|| {
+        LIST_MARKERS
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+    })
+}
+
+/// The file extension of a note. Only markdown notes have front matter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Extension {
+    #[default]
+    Md,
+    Txt,
+}
+
+impl Extension {
+    /// Returns the extension without its leading dot, e.g. "md".
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Extension::Md => "md",
+            Extension::Txt => "txt",
+        }
+    }
+
+    /// Returns the extension of a note file, if it is one.
+    pub fn of(path: &Path) -> Option<Self> {
+        let ext = path.extension()?;
+        Extension::value_variants()
+            .iter()
+            .copied()
+            .find(|variant| ext == variant.as_str())
+    }
+}
+
+/// Returns the directory holding the notes of a date, e.g. `~/.til/notes/08-18-2024`,
+/// named in the configured date format.
+pub fn day_path(root_dir: &Path, date: NaiveDate) -> crate::error::Result<PathBuf> {
+    Ok(day_path_in(root_dir, date, Config::load()?.date_format))
+}
+
+fn day_path_in(root_dir: &Path, date: NaiveDate, format: DateFormat) -> PathBuf {
+    root_dir.join(format.format(date))
+}
+
+/// Returns the path of a note from its date and title, e.g.
+/// `~/.til/notes/08-18-2024/default.md`. The note doesn't need to exist.
+///
+/// ## Errors
+///
+/// * `Error::InvalidTitle` - If the title cannot be used as a file name.
+pub fn note_path(root_dir: &Path, date: NaiveDate, title: &str) -> crate::error::Result<PathBuf> {
+    note_in(&day_path(root_dir, date)?, title)
+}
+
+/// Returns the path of a note with the given title within a date directory.
+///
+/// An existing `.txt` note is returned when there is no markdown note with that
+/// title, otherwise the path of the markdown note is returned.
+///
+/// ## Errors
+///
+/// * `Error::InvalidTitle` - If the title cannot be used as a file name.
+pub fn note_in(directory: &Path, title: &str) -> crate::error::Result<PathBuf> {
+    let markdown = note_with_extension(directory, title, Extension::Md)?;
+    if markdown.exists() {
+        return Ok(markdown);
+    }
+
+    let text = markdown.with_extension(Extension::Txt.as_str());
+    Ok(if text.exists() { text } else { markdown })
+}
+
+/// Returns the path of a note with the given title and extension within a date
+/// directory.
+///
+/// ## Errors
+///
+/// * `Error::InvalidTitle` - If the title cannot be used as a file name.
+pub fn note_with_extension(
+    directory: &Path,
+    title: &str,
+    extension: Extension,
+) -> crate::error::Result<PathBuf> {
+    let mut path = directory.join(sanitize_title(title)?);
+    path.set_extension(extension.as_str());
+    Ok(path)
+}
+
+/// Returns the directory of a topic such as `rust/async` within a date directory.
+///
+/// Every segment of the topic is sanitized like a title so that a topic can't
+/// point outside of the date directory, and empty segments are skipped.
+///
+/// ## Errors
+///
+/// * `Error::InvalidTopic` - If a segment of the topic cannot be used as a directory name.
+pub fn topic_dir(directory: &Path, topic: &str) -> crate::error::Result<PathBuf> {
+    topic
+        .split(['/', '\\'])
+        .filter(|segment| !segment.trim().is_empty())
+        .try_fold(directory.to_path_buf(), |path, segment| {
+            let segment =
+                sanitize_title(segment).map_err(|_| Error::InvalidTopic(topic.to_owned()))?;
+            Ok(path.join(segment))
+        })
+}
+
+/// Turns a note title into a file name that stays within its date directory.
+///
+/// Path separators and control characters are replaced with `-`, runs of whitespace
+/// are collapsed into a single `-`, and leading dots are removed so a title can't
+/// point to a parent or hidden file.
+///
+/// ## Errors
+///
+/// * `Error::InvalidTitle` - If nothing is left of the title once sanitized.
+pub fn sanitize_title(title: &str) -> crate::error::Result<String> {
+    let sanitized = title
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .replace(|c: char| c == '/' || c == '\\' || c.is_control(), "-")
+        .trim_start_matches('.')
+        .to_owned();
+
+    if sanitized.is_empty() {
+        return Err(Error::InvalidTitle(title.to_owned()));
+    }
+
+    Ok(sanitized)
+}
+
+/// Reads a note, decrypting its body when it was encrypted.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If the note cannot be read.
+/// * `Error::NoPassphrase` - If the note is encrypted and no passphrase is available.
+/// * `Error::CannotDecrypt` - If the note is encrypted and cannot be decrypted.
+pub fn read(path: &Path) -> crate::error::Result<String> {
+    read_sealed(path).map(|(contents, _)| contents)
+}
+
+/// Reads a note like [`read`], along with whether it was encrypted, so that it can
+/// be written back encrypted with [`write_note`].
+///
+/// ## Errors
+///
+/// The same as [`read`].
+pub fn read_sealed(path: &Path) -> crate::error::Result<(String, bool)> {
+    let contents =
+        fs::read_to_string(path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))?;
+
+    if crypto::is_sealed(&contents) {
+        Ok((crypto::open(&contents, path)?, true))
+    } else {
+        Ok((contents, false))
+    }
+}
+
+/// Writes a note with [`write_atomically`], encrypting its body first when `seal`
+/// is set.
+///
+/// ## Errors
+///
+/// * Any error returned by [`crypto::seal`] or [`write_atomically`].
+pub fn write_note(
+    root_dir: &Path,
+    path: &Path,
+    contents: &str,
+    seal: bool,
+) -> crate::error::Result<()> {
+    if seal {
+        write_atomically(path, &crypto::seal(contents, root_dir)?)
+    } else {
+        write_atomically(path, contents)
+    }
+}
+
+/// Replaces the contents of a file all at once by writing them to a temporary file
+/// next to it, then renaming it over the file. The file is left untouched when the
+/// contents cannot be written in full, and keeps its permissions otherwise.
+///
+/// ## Errors
+///
+/// * `Error::CannotOpenOrCreatePath` - If the temporary file cannot be created.
+/// * `Error::CannotWriteToFile` - If the contents cannot be written or the file replaced.
+pub fn write_atomically(path: &Path, contents: &str) -> crate::error::Result<()> {
+    replace_with(path, |file| file.write_all(contents.as_bytes()))
+}
+
+fn replace_with(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> io::Result<()>,
+) -> crate::error::Result<()> {
+    let temporary = temporary_path(path);
+
+    let mut file = File::create(&temporary)
+        .map_err(|err| Error::CannotOpenOrCreatePath(temporary.clone(), err))?;
+    let replaced = write(&mut file)
+        .and_then(|_| file.sync_all())
+        .and_then(|_| match fs::metadata(path) {
+            Ok(metadata) => fs::set_permissions(&temporary, metadata.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temporary, path));
+
+    replaced.map_err(|err| {
+        let _ = fs::remove_file(&temporary);
+        Error::CannotWriteToFile(path.to_path_buf(), err)
+    })
+}
+
+/// Returns the hidden file next to a file its new contents are written to before
+/// replacing it, e.g. `.default.md.tmp`.
+pub fn temporary_path(path: &Path) -> PathBuf {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{file_name}.tmp"))
+}
+
+/// Removes a directory once it no longer holds any files.
+///
+/// ## Errors
+///
+/// * `Error::CannotRemoveFile` - If the empty directory cannot be removed.
+pub fn remove_dir_if_empty(directory: &Path) -> crate::error::Result<()> {
+    let is_empty = fs::read_dir(directory)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+
+    if is_empty {
+        fs::remove_dir(directory)
+            .map_err(|err| Error::CannotRemoveFile(directory.to_path_buf(), err))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the directory old date directories of a notes directory are archived
+/// to with `til archive`, e.g. `~/.til/notes/archive`.
+pub fn archive_dir_in(root_dir: &Path) -> PathBuf {
+    root_dir.join(ARCHIVE_DIR)
+}
+
+/// Lists every note day within a notes directory, or within its archive, sorted
+/// chronologically.
+///
+/// Directories named after a date in either the MM-DD-YYYY or the YYYY-MM-DD format
+/// are days, whichever format is configured. Other directories, such as the archive,
+/// are ignored, and a missing root directory simply has no days.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If the directory cannot be read.
+pub fn days(root_dir: &Path) -> crate::error::Result<Vec<Day>> {
+    if !root_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut days = fs::read_dir(root_dir)
+        .map_err(|err| Error::CannotReadFile(root_dir.to_path_buf(), err))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_str()?;
+            let (_, date) = DateFormat::detect(name)?;
+            Some(Day { date, path })
+        })
+        .collect::<Vec<_>>();
+    days.sort_by_key(|day| day.date);
+
+    Ok(days)
+}
+
+/// Lists every `.md` and `.txt` note within a date directory, including the notes
+/// filed under topic directories, sorted by path.
+///
+/// Hidden files and directories, e.g. `.DS_Store`, are skipped along with any file
+/// which isn't a note.
+pub fn notes_in(directory: &Path) -> crate::error::Result<Vec<PathBuf>> {
+    let (mut notes, mut stray) = (Vec::new(), Vec::new());
+    scan(directory, &mut notes, &mut stray)?;
+    notes.sort();
+
+    Ok(notes)
+}
+
+/// Lists the files within the date directories which are skipped as they aren't
+/// notes, e.g. "08-18-2024/diagram.png", leaving out hidden files.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If a directory cannot be read.
+pub fn stray_files(root_dir: &Path) -> crate::error::Result<Vec<String>> {
+    let mut names = Vec::new();
+    for day in days(root_dir)? {
+        let (mut notes, mut stray) = (Vec::new(), Vec::new());
+        scan(&day.path, &mut notes, &mut stray)?;
+        stray.sort();
+        names.extend(stray.iter().map(|path| day.note_name(path)));
+    }
+
+    Ok(names)
+}
+
+/// Sorts the files within a directory and its visible subdirectories into notes and
+/// stray files, skipping hidden entries.
+fn scan(
+    directory: &Path,
+    notes: &mut Vec<PathBuf>,
+    stray: &mut Vec<PathBuf>,
+) -> crate::error::Result<()> {
+    let entries = fs::read_dir(directory)
+        .map_err(|err| Error::CannotReadFile(directory.to_path_buf(), err))?;
+
+    for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+        let is_hidden = path
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+
+        if is_hidden {
+            continue;
+        } else if path.is_dir() {
+            scan(&path, notes, stray)?;
+        } else if Extension::of(&path).is_some() {
+            notes.push(path);
+        } else {
+            stray.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists the notes of every day along with their names, e.g. "08-18-2024/default.md",
+/// sorted chronologically.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If a directory cannot be read.
+pub fn named_notes(root_dir: &Path) -> crate::error::Result<Vec<(String, PathBuf)>> {
+    let mut named = Vec::new();
+    for day in days(root_dir)? {
+        for path in notes_in(&day.path)? {
+            named.push((day.note_name(&path), path));
+        }
+    }
+
+    Ok(named)
+}
+
+/// Returns the body of a note, skipping its front matter block.
+pub fn body(contents: &str) -> &str {
+    contents
+        .split_once("\n---\n")
+        .map_or(contents, |(_, body)| body)
+}
+
+/// Returns the bullets of a note without their prefix, skipping its front matter.
+pub fn bullets(contents: &str) -> Vec<String> {
+    body(contents)
+        .lines()
+        .filter_map(strip_bullet)
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Returns the title of a note from its front matter, falling back to its file name
+/// when the front matter has none.
+pub fn title(path: &Path, meta: &FrontMatter) -> String {
+    if meta.title.is_empty() {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    } else {
+        meta.title.clone()
+    }
+}
+
+/// Returns the tags listed in the front matter of a note, trimmed and without
+/// any empty tags. A note without a `tags` field, or without front matter, has no tags.
+pub fn tags(contents: &str) -> Vec<String> {
+    FrontMatter::parse(contents)
+        .map(|meta| meta.tags)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{self, Write},
+        path::Path,
+    };
+
+    use super::{
+        body, bullets, day_path_in, days, note_in, notes_in, replace_with, sanitize_title, scan,
+        strip_bullet_with, tags, topic_dir, validate_bullet_prefix, write_atomically, Day,
+        Extension, Note,
+    };
+    use crate::{config::DateFormat, date::parse_date};
+
+    #[test]
+    fn titles_cannot_escape_the_date_directory() {
+        assert_eq!(sanitize_title("rust").unwrap(), "rust");
+        assert_eq!(sanitize_title("a/b").unwrap(), "a-b");
+        assert_eq!(sanitize_title("../escape").unwrap(), "-escape");
+        assert!(sanitize_title("..").is_err());
+        assert!(sanitize_title(" \t ").is_err());
+    }
+
+    #[test]
+    fn titles_are_safe_file_names() {
+        assert_eq!(
+            sanitize_title("  my   rust\tnotes ").unwrap(),
+            "my-rust-notes"
+        );
+        assert_eq!(sanitize_title("null\0byte").unwrap(), "null-byte");
+        assert_eq!(sanitize_title("日本語 ノート").unwrap(), "日本語-ノート");
+        assert_eq!(sanitize_title("café").unwrap(), "café");
+    }
+
+    #[test]
+    fn note_paths_round_trip_through_days() {
+        let root_dir = env::temp_dir().join(format!("til-paths-{}", std::process::id()));
+        let date = parse_date("1-5-2024").unwrap();
+
+        let written =
+            note_in(&day_path_in(&root_dir, date, DateFormat::Legacy), "default").unwrap();
+        let recalled = note_in(
+            &day_path_in(
+                &root_dir,
+                parse_date("01-05-2024").unwrap(),
+                DateFormat::Legacy,
+            ),
+            "default",
+        )
+        .unwrap();
+        assert_eq!(written, recalled);
+        assert!(written.ends_with("01-05-2024/default.md"));
+
+        fs::create_dir_all(written.parent().unwrap()).unwrap();
+        let days = days(&root_dir).unwrap();
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert!(matches!(days.as_slice(), [Day { date: found, .. }] if *found == date));
+    }
+
+    #[test]
+    fn topics_cannot_escape_the_date_directory() {
+        let directory = Path::new("08-18-2024");
+
+        assert_eq!(
+            topic_dir(directory, "rust/async").unwrap(),
+            directory.join("rust").join("async")
+        );
+        assert_eq!(
+            topic_dir(directory, "/rust//my async/").unwrap(),
+            directory.join("rust").join("my-async")
+        );
+        assert!(topic_dir(directory, "../../etc").is_err());
+        assert!(topic_dir(directory, "rust/..").is_err());
+    }
+
+    #[test]
+    fn topic_notes_are_listed_with_their_day() {
+        let root_dir = env::temp_dir().join(format!("til-topics-{}", std::process::id()));
+        let day_dir = day_path_in(
+            &root_dir,
+            parse_date("08-18-2024").unwrap(),
+            DateFormat::Legacy,
+        );
+        let topic = topic_dir(&day_dir, "rust/async").unwrap();
+        fs::create_dir_all(&topic).unwrap();
+        fs::write(day_dir.join("default.md"), "").unwrap();
+        fs::write(topic.join("tokio.md"), "").unwrap();
+        fs::write(topic.join("tokio.txt"), "").unwrap();
+        fs::write(topic.join("tokio.html"), "").unwrap();
+
+        let day = Day {
+            date: parse_date("08-18-2024").unwrap(),
+            path: day_dir.clone(),
+        };
+        let names = notes_in(&day_dir)
+            .unwrap()
+            .iter()
+            .map(|path| day.note_name(path))
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert_eq!(
+            names,
+            [
+                "08-18-2024/default.md",
+                "08-18-2024/rust/async/tokio.md",
+                "08-18-2024/rust/async/tokio.txt"
+            ]
+        );
+    }
+
+    #[test]
+    fn text_notes_are_found_by_title() {
+        let directory = env::temp_dir().join(format!("til-text-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        let missing = note_in(&directory, "plain").unwrap();
+        fs::write(directory.join("plain.txt"), "- note\n").unwrap();
+        let text = note_in(&directory, "plain").unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(Extension::of(&missing), Some(Extension::Md));
+        assert_eq!(Extension::of(&text), Some(Extension::Txt));
+    }
+
+    #[test]
+    fn failed_writes_leave_the_note_intact() {
+        let directory = env::temp_dir().join(format!("til-atomic-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("default.md");
+        fs::write(&path, "---\ntitle: \"default\"\n---\n\n- one\n").unwrap();
+
+        let failed = replace_with(&path, |file| {
+            file.write_all(b"---\ntitle: \"def")?;
+            Err(io::Error::other("disk full"))
+        });
+        let after_failure = fs::read_to_string(&path).unwrap();
+        let leftovers = fs::read_dir(&directory).unwrap().count();
+
+        write_atomically(&path, "- two\n").unwrap();
+        let after_success = fs::read_to_string(&path).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(failed.is_err());
+        assert_eq!(after_failure, "---\ntitle: \"default\"\n---\n\n- one\n");
+        assert_eq!(leftovers, 1);
+        assert_eq!(after_success, "- two\n");
+    }
+
+    #[test]
+    fn tags_are_trimmed() {
+        let contents = "---\ntitle: \"default\"\ntags: [rust,  async, ]\n---\n\n- note\n";
+
+        assert_eq!(tags(contents), ["rust", "async"]);
+        assert!(tags("---\ntitle: \"default\"\n---\n").is_empty());
+    }
+
+    #[test]
+    fn days_are_sorted_chronologically() {
+        let root_dir = env::temp_dir().join(format!("til-days-{}", std::process::id()));
+        for name in [
+            "10-1-2024",
+            "9-30-2024",
+            "2024-09-29",
+            "01-15-2023",
+            "not-a-date",
+        ] {
+            fs::create_dir_all(root_dir.join(name)).unwrap();
+        }
+
+        let names = days(&root_dir)
+            .unwrap()
+            .iter()
+            .map(|day| day.name())
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert_eq!(
+            names,
+            ["01-15-2023", "2024-09-29", "9-30-2024", "10-1-2024"]
+        );
+    }
+
+    #[test]
+    fn body_skips_front_matter() {
+        let contents = "---\ntitle: \"default\"\ntags: [rust]\n---\n\n- note\n";
+
+        assert_eq!(body(contents), "\n- note\n");
+        assert_eq!(body("- note\n"), "- note\n");
+    }
+
+    #[test]
+    fn bullets_are_stripped_of_their_prefix() {
+        let contents = "---\ntitle: \"default\"\ntags: []\n---\n\n- one\n- [14:32] two\n";
+
+        assert_eq!(bullets(contents), ["one", "[14:32] two"]);
+    }
+
+    #[test]
+    fn configured_bullet_prefixes_are_stripped_along_with_the_default_one() {
+        assert_eq!(strip_bullet_with("* one", "* "), Some("one"));
+        assert_eq!(strip_bullet_with("- two", "* "), Some("two"));
+        assert_eq!(strip_bullet_with("+ two", "1. "), Some("two"));
+        assert_eq!(strip_bullet_with("1. three", "1. "), Some("three"));
+        assert_eq!(strip_bullet_with("not a bullet", "* "), None);
+
+        assert!(validate_bullet_prefix("* ").is_ok());
+        assert!(validate_bullet_prefix("").is_err());
+        assert!(validate_bullet_prefix("  ").is_err());
+        assert!(validate_bullet_prefix("-\n").is_err());
+    }
+
+    #[test]
+    fn notes_are_read_from_their_date_directory() {
+        let root_dir = env::temp_dir().join(format!("til-note-{}", std::process::id()));
+        let directory = root_dir.join("08-18-2024").join("rust");
+        fs::create_dir_all(&directory).unwrap();
+
+        let written = directory.join("pinning.md");
+        fs::write(
+            &written,
+            "---\ntitle: \"Pinning\"\ntags: [rust, async]\nsource: book\n---\n\n- pin it\n  - nested\ntext\n- unpin it\n",
+        )
+        .unwrap();
+        let note = Note::from_file(&written).unwrap();
+
+        let broken = directory.join("broken.md");
+        fs::write(&broken, "---\ntags: [\n---\n- still a bullet\n").unwrap();
+        let broken = Note::from_file(&broken).unwrap();
+
+        let outside = root_dir.join("drafts.md");
+        fs::write(&outside, "- no date\n").unwrap();
+        let outside = Note::from_file(&outside);
+        let missing = Note::from_file(&directory.join("missing.md"));
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert_eq!(note.date, parse_date("08-18-2024").unwrap());
+        assert_eq!(note.title, "Pinning");
+        assert_eq!(note.tags, ["rust", "async"]);
+        assert_eq!(note.bullets, ["pin it", "unpin it"]);
+        assert_eq!(note.body, "- pin it\n  - nested\ntext\n- unpin it\n");
+
+        assert_eq!(broken.title, "broken");
+        assert!(broken.tags.is_empty());
+        assert_eq!(broken.bullets, ["still a bullet"]);
+
+        assert!(outside.is_err());
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn notes_round_trip_through_markdown() {
+        let contents = "---\ntitle: \"Pinning\"\ntags: [rust]\ncreated: 2024-10-07T09:00:00+02:00\nupdated: 2024-10-07T09:00:00+02:00\nsource: book\n---\n\n- pin it\n";
+        let date = parse_date("10-07-2024").unwrap();
+
+        assert_eq!(
+            Note::parse(date, Path::new("pinning.md"), contents).to_markdown(),
+            contents
+        );
+        assert_eq!(
+            Note::new(date, "rust", &["a".to_owned()], vec!["one".to_owned()]).to_markdown(),
+            "---\ntitle: \"rust\"\ntags: [a]\n---\n\n- one\n"
+        );
+    }
+
+    #[test]
+    fn only_visible_notes_are_listed() {
+        let directory = env::temp_dir().join(format!("til-mixed-{}", std::process::id()));
+        fs::create_dir_all(directory.join("rust")).unwrap();
+        fs::create_dir_all(directory.join(".hidden")).unwrap();
+        for name in [
+            "default.md",
+            "plain.txt",
+            "rust/async.md",
+            "diagram.png",
+            ".DS_Store",
+            "._default.md",
+            ".default.md.tmp",
+            ".hidden/secret.md",
+        ] {
+            fs::write(directory.join(name), "- note\n").unwrap();
+        }
+
+        let listed = notes_in(&directory).unwrap();
+        let (mut notes, mut stray) = (Vec::new(), Vec::new());
+        scan(&directory, &mut notes, &mut stray).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(
+            listed,
+            [
+                directory.join("default.md"),
+                directory.join("plain.txt"),
+                directory.join("rust/async.md")
+            ]
+        );
+        assert_eq!(stray, [directory.join("diagram.png")]);
+    }
+}
@@ -0,0 +1,58 @@
+use std::{fs, path::Path, process};
+
+use clap::Args;
+
+use crate::{date::parse_date, editor, error::Error, notes};
+
+#[cfg(target_os = "macos")]
+const FILE_MANAGER: &str = "open";
+#[cfg(windows)]
+const FILE_MANAGER: &str = "explorer";
+#[cfg(not(any(target_os = "macos", windows)))]
+const FILE_MANAGER: &str = "xdg-open";
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Open {
+    /// Open the directory of a single date ("MM-DD-YYYY", "yesterday", ...) instead
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Open the directory in $EDITOR instead of the file manager
+    #[clap(short, long)]
+    pub editor: bool,
+}
+
+impl Open {
+    /// Opens the notes directory, or the directory of a single date, in the platform
+    /// file manager or `$EDITOR`. The directory is created when it doesn't exist yet.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotCreateDir` - If the directory cannot be created.
+    /// * `Error::CannotLaunchFileManager` - If the file manager cannot be started.
+    /// * Any error returned by `editor::open`.
+    pub fn launch(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let directory = match &self.date {
+            Some(date) => notes::day_path(root_dir, parse_date(date)?)?,
+            None => root_dir.to_path_buf(),
+        };
+
+        fs::create_dir_all(&directory)
+            .map_err(|err| Error::CannotCreateDir(directory.display().to_string(), err))?;
+
+        if self.editor {
+            return editor::open(&directory);
+        }
+
+        // `explorer` exits unsuccessfully even when it opens the directory, so only a
+        // failure to launch is reported.
+        process::Command::new(FILE_MANAGER)
+            .arg(&directory)
+            .status()
+            .map_err(|_| Error::CannotLaunchFileManager(FILE_MANAGER.to_owned()))?;
+
+        Ok(())
+    }
+}
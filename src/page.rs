@@ -0,0 +1,88 @@
+use clap::Args;
+
+/// Options capping how many results a command prints.
+#[derive(Args, Debug, Default)]
+#[group(skip)]
+pub struct Page {
+    /// Print at most this many results
+    #[clap(long)]
+    pub limit: Option<usize>,
+
+    /// Skip this many results before printing any
+    #[clap(long, default_value_t = 0)]
+    pub skip: usize,
+}
+
+impl Page {
+    /// Keeps the results within the page, telling the user on stderr how many
+    /// more results were left out after it.
+    pub fn apply<T>(&self, results: Vec<T>) -> Vec<T> {
+        let total = results.len();
+        let (page, _) = paginate(results, self.skip, self.limit);
+        self.report(total);
+
+        page
+    }
+
+    /// Checks whether the result at `index` falls within the page, for results
+    /// which are written as they are found rather than collected first.
+    pub fn contains(&self, index: usize) -> bool {
+        index >= self.skip && self.limit.is_none_or(|limit| index - self.skip < limit)
+    }
+
+    /// Tells the user on stderr how many of the `total` results were left out after
+    /// the page.
+    pub fn report(&self, total: usize) {
+        let shown = total
+            .saturating_sub(self.skip)
+            .min(self.limit.unwrap_or(usize::MAX));
+        let remaining = total.saturating_sub(self.skip) - shown;
+
+        if remaining > 0 {
+            eprintln!(
+                "{remaining} more not shown, pass --skip {} to see them",
+                self.skip + shown
+            );
+        }
+    }
+}
+
+/// Skips and limits the results, returning the ones kept along with the number of
+/// results left after them.
+fn paginate<T>(results: Vec<T>, skip: usize, limit: Option<usize>) -> (Vec<T>, usize) {
+    let mut kept: Vec<T> = results.into_iter().skip(skip).collect();
+    let remaining = match limit {
+        Some(limit) if kept.len() > limit => kept.split_off(limit).len(),
+        _ => 0,
+    };
+
+    (kept, remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paginate, Page};
+
+    #[test]
+    fn pages_count_what_is_left_after_them() {
+        let results = vec![1, 2, 3, 4, 5];
+
+        assert_eq!(paginate(results.clone(), 1, Some(2)), (vec![2, 3], 2));
+        assert_eq!(paginate(results.clone(), 3, None), (vec![4, 5], 0));
+        assert_eq!(paginate(results, 10, Some(2)), (vec![], 0));
+    }
+
+    #[test]
+    fn streamed_results_match_their_page() {
+        let page = Page {
+            limit: Some(2),
+            skip: 1,
+        };
+        let kept = (0..5)
+            .filter(|&index| page.contains(index))
+            .collect::<Vec<_>>();
+
+        assert_eq!(kept, paginate((0..5).collect(), 1, Some(2)).0);
+        assert!(Page::default().contains(100));
+    }
+}
@@ -0,0 +1,23 @@
+use std::path::Path;
+
+use crate::index::Index;
+
+/// Lists every pinned note across all dates, oldest first, as its name followed by
+/// its title, e.g. `08-18-2024/default.md  Rust lifetimes`.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If a note or directory cannot be read while indexing.
+pub fn pinned(root_dir: &Path) -> crate::error::Result<Vec<String>> {
+    let notes = Index::load(root_dir)?
+        .notes
+        .into_iter()
+        .filter(|note| note.pinned)
+        .collect::<Vec<_>>();
+    let width = notes.iter().map(|note| note.name.len()).max().unwrap_or(0);
+
+    Ok(notes
+        .iter()
+        .map(|note| format!("{:width$}  {}", note.name, note.title))
+        .collect())
+}
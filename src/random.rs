@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use chrono::{Days, Local};
+use clap::Args;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Random {
+    /// Only pick from notes at least this many days old
+    #[clap(long, default_value_t = 0)]
+    pub days: u64,
+
+    /// Seed the random pick, so the same seed always picks the same bullet
+    #[clap(long)]
+    pub seed: Option<u64>,
+}
+
+/// A note to pick from, formatted as `MM-DD-YYYY/title.md`, along with its bullets.
+type Candidate = (String, Vec<String>);
+
+impl Random {
+    /// Picks a random bullet from a random note, formatted as
+    /// `MM-DD-YYYY/title.md: - bullet`.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::NoNotesFound` - If no note old enough has any bullet.
+    pub fn pick(&self, root_dir: &Path) -> crate::error::Result<String> {
+        let newest = Local::now()
+            .date_naive()
+            .checked_sub_days(Days::new(self.days))
+            .unwrap_or_default();
+        let mut candidates = Vec::new();
+
+        for day in notes::days(root_dir)?
+            .into_iter()
+            .filter(|day| day.date <= newest)
+        {
+            for path in notes::notes_in(&day.path)? {
+                let contents = notes::read(&path)?;
+                let bullets = notes::bullets(&contents);
+
+                if !bullets.is_empty() {
+                    candidates.push((day.note_name(&path), bullets));
+                }
+            }
+        }
+
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        choose(&candidates, &mut rng).ok_or_else(|| {
+            Error::NoNotesFound(match self.days {
+                0 => "any date".to_owned(),
+                days => format!("{days} days ago or earlier"),
+            })
+        })
+    }
+}
+
+/// Picks a random note, then a random bullet within it.
+fn choose(candidates: &[Candidate], rng: &mut StdRng) -> Option<String> {
+    let (note, bullets) = candidates.choose(rng)?;
+    let bullet = bullets.choose(rng)?;
+
+    Some(format!("{note}: - {bullet}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::choose;
+
+    #[test]
+    fn seeded_picks_are_repeatable() {
+        let candidates = vec![
+            (
+                "10-06-2024/default.md".to_string(),
+                vec!["a".to_string(), "b".to_string()],
+            ),
+            ("10-07-2024/rust.md".to_string(), vec!["c".to_string()]),
+        ];
+
+        let first = choose(&candidates, &mut StdRng::seed_from_u64(7)).unwrap();
+        let second = choose(&candidates, &mut StdRng::seed_from_u64(7)).unwrap();
+
+        assert_eq!(first, second);
+        assert!(choose(&[], &mut StdRng::seed_from_u64(7)).is_none());
+    }
+}
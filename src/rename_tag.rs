@@ -0,0 +1,75 @@
+use std::{fs, path::Path};
+
+use clap::Args;
+
+use crate::{config::Config, error::Error, front_matter::FrontMatter, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct RenameTag {
+    /// The tag to rename
+    pub old: String,
+
+    /// The new name of the tag
+    pub new: String,
+}
+
+impl RenameTag {
+    /// Renames a tag in the front matter of every note carrying it, returning a line
+    /// per changed note followed by a summary. With `dry_run`, nothing is rewritten.
+    ///
+    /// Notes already carrying the new tag simply lose the old one. The old tag is
+    /// matched ignoring case with `case_insensitive_tags` configured. Notes without
+    /// front matter are left alone, with a warning.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTag` - If the new tag is empty.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotWriteToFile` - If a note cannot be rewritten.
+    pub fn rename(&self, root_dir: &Path, dry_run: bool) -> crate::error::Result<Vec<String>> {
+        let old = self.old.trim();
+        let new = self.new.trim();
+        if new.is_empty() {
+            return Err(Error::InvalidTag(self.new.clone()));
+        }
+
+        let ignore_case = Config::load()?.case_insensitive_tags;
+        let mut lines = Vec::new();
+
+        for day in notes::days(root_dir)? {
+            for path in notes::notes_in(&day.path)? {
+                let contents = fs::read_to_string(&path)
+                    .map_err(|err| Error::CannotReadFile(path.clone(), err))?;
+                if !contents.starts_with("---\n") {
+                    eprintln!(
+                        "warning: {} has no front matter, so it has no tags to rename",
+                        path.display()
+                    );
+                    continue;
+                }
+                let Ok((mut meta, body)) = FrontMatter::split(&contents) else {
+                    continue;
+                };
+
+                if !meta.rename_tag(old, new, ignore_case) {
+                    continue;
+                }
+
+                if !dry_run {
+                    notes::write_atomically(&path, &format!("{}{}", meta.render(), body))?;
+                }
+
+                lines.push(day.note_name(&path));
+            }
+        }
+
+        let verb = if dry_run { "would rename" } else { "renamed" };
+        lines.push(format!(
+            "{verb} \"{old}\" to \"{new}\" in {} note(s)",
+            lines.len()
+        ));
+
+        Ok(lines)
+    }
+}
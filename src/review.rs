@@ -0,0 +1,263 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, BufRead, Write},
+    path::Path,
+};
+
+use chrono::{Days, Local, NaiveDate};
+use clap::{Args, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::Config,
+    date,
+    error::Error,
+    index::{Index, IndexedNote},
+    notes, style,
+};
+
+/// The file within the notes directory keeping when each note is due for review.
+const REVIEWS_FILE: &str = ".reviews.json";
+/// How easy a note starts out being to recall, as the factor its interval grows by.
+const INITIAL_EASE: f64 = 2.5;
+/// The lowest the ease of a note falls to, so that its interval keeps growing.
+const MIN_EASE: f64 = 1.3;
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Review {
+    /// Schedule the note with this grade instead of prompting for it
+    #[clap(long, value_enum)]
+    pub grade: Option<Grade>,
+}
+
+/// How well a reviewed note was recalled.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum Grade {
+    /// Not recalled, the note is reviewed again tomorrow
+    Again,
+    /// Recalled with difficulty
+    Hard,
+    /// Recalled
+    Good,
+    /// Recalled without effort
+    Easy,
+}
+
+impl Grade {
+    /// The quality of the recall on the 0 to 5 scale of SM-2.
+    fn quality(self) -> f64 {
+        match self {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        }
+    }
+
+    fn parse(answer: &str) -> Option<Self> {
+        match answer.trim().to_lowercase().as_str() {
+            "a" | "again" => Some(Grade::Again),
+            "h" | "hard" => Some(Grade::Hard),
+            "g" | "good" | "" => Some(Grade::Good),
+            "e" | "easy" => Some(Grade::Easy),
+            _ => None,
+        }
+    }
+}
+
+/// When a note is next due for review, and how its interval grows.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+struct Schedule {
+    due: NaiveDate,
+    /// The number of days until the next review.
+    interval: u64,
+    /// How many times in a row the note was recalled.
+    repetitions: u32,
+    ease: f64,
+}
+
+impl Review {
+    /// Prints the note due for review the longest, then prompts for how well it
+    /// was recalled, unless given with `--grade`, and schedules its next review.
+    ///
+    /// Notes never reviewed are due from the start, oldest first. A note recalled
+    /// again is due after a day, then after six days, then after its previous
+    /// interval multiplied by its ease, which grows when recalled easily and shrinks
+    /// when recalled with difficulty, following SM-2. A note which wasn't recalled
+    /// starts over. Schedules are kept in a `.reviews.json` file within the notes
+    /// directory, and answering anything other than a grade skips the note.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note, directory or the review schedules cannot be read.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    /// * `Error::CannotWriteToFile` - If the review schedules cannot be saved.
+    /// * `Error::CannotReadStdin` - If the grade cannot be read.
+    pub fn review(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let config = Config::load()?;
+        let today = Local::now().date_naive();
+        let path = root_dir.join(REVIEWS_FILE);
+        let mut schedules = load(&path)?;
+
+        let notes = Index::load(root_dir)?
+            .notes
+            .into_iter()
+            .map(IndexedNote::opened)
+            .filter(|note| note.as_ref().map_or(true, |note| !note.bullets.is_empty()))
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        schedules.retain(|name, _| notes.iter().any(|note| note.name == *name));
+
+        let mut due = notes
+            .iter()
+            .filter_map(|note| {
+                let due = schedules
+                    .get(&note.name)
+                    .map_or(note.date, |schedule| schedule.due);
+                (due <= today).then_some((due, note))
+            })
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(due, _)| *due);
+
+        let Some((_, note)) = due.first() else {
+            let next = schedules.values().map(|schedule| schedule.due).min();
+            match next {
+                Some(next) => println!(
+                    "nothing is due for review until {}",
+                    date::display_date(next, &config)
+                ),
+                None => println!("no notes to review yet"),
+            }
+            return Ok(());
+        };
+
+        println!("{}\n", style::heading(&note.name));
+        println!("{}\n", style::note(notes::read(&note.path)?.trim()));
+
+        let Some(grade) = self.grade.map_or_else(prompt, |grade| Ok(Some(grade)))? else {
+            println!("skipped {}", note.name);
+            return Ok(());
+        };
+
+        let schedule = schedule(schedules.get(&note.name), grade, today);
+        println!(
+            "next review of {} in {} day(s), on {}",
+            note.name,
+            schedule.interval,
+            date::display_date(schedule.due, &config)
+        );
+        schedules.insert(note.name.clone(), schedule);
+        save(&path, &schedules)?;
+
+        if due.len() > 1 {
+            println!("{} more due", due.len() - 1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Asks how well the note was recalled, returning `None` when the answer isn't a
+/// grade or stdin is closed.
+fn prompt() -> crate::error::Result<Option<Grade>> {
+    print!("how well did you recall it? [a]gain, [h]ard, [G]ood, [e]asy: ");
+    io::stdout()
+        .flush()
+        .map_err(|err| Error::CannotWriteToFile("stdout".into(), err))?;
+
+    let mut answer = String::new();
+    let read = io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .map_err(|_| Error::CannotReadStdin)?;
+
+    Ok(if read == 0 {
+        None
+    } else {
+        Grade::parse(&answer)
+    })
+}
+
+/// Schedules the next review of a note recalled with the given grade today,
+/// following SM-2.
+fn schedule(previous: Option<&Schedule>, grade: Grade, today: NaiveDate) -> Schedule {
+    let (interval, repetitions, ease) = previous.map_or((0, 0, INITIAL_EASE), |previous| {
+        (previous.interval, previous.repetitions, previous.ease)
+    });
+    let quality = grade.quality();
+    let ease = (ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+
+    let (interval, repetitions) = match (grade, repetitions) {
+        (Grade::Again, _) => (1, 0),
+        (_, 0) => (1, 1),
+        (_, 1) => (6, 2),
+        (_, repetitions) => ((interval as f64 * ease).round() as u64, repetitions + 1),
+    };
+
+    Schedule {
+        due: today + Days::new(interval),
+        interval,
+        repetitions,
+        ease,
+    }
+}
+
+fn load(path: &Path) -> crate::error::Result<BTreeMap<String, Schedule>> {
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    let contents =
+        fs::read_to_string(path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| Error::Custom(format!("cannot parse {}: {err}", path.display())))
+}
+
+fn save(path: &Path, schedules: &BTreeMap<String, Schedule>) -> crate::error::Result<()> {
+    let contents =
+        serde_json::to_string_pretty(schedules).map_err(|err| Error::Custom(err.to_string()))?;
+    notes::write_atomically(path, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{schedule, Grade};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 10, day).unwrap()
+    }
+
+    #[test]
+    fn intervals_grow_with_each_recall() {
+        let first = schedule(None, Grade::Good, date(1));
+        assert_eq!((first.interval, first.due), (1, date(2)));
+
+        let second = schedule(Some(&first), Grade::Good, date(2));
+        assert_eq!((second.interval, second.due), (6, date(8)));
+
+        let third = schedule(Some(&second), Grade::Good, date(8));
+        assert_eq!((third.interval, third.due), (15, date(23)));
+
+        let easy = schedule(Some(&second), Grade::Easy, date(8));
+        let hard = schedule(Some(&second), Grade::Hard, date(8));
+        assert!(easy.interval > third.interval && hard.interval < third.interval);
+    }
+
+    #[test]
+    fn forgotten_notes_start_over() {
+        let mut schedule_of = schedule(None, Grade::Easy, date(1));
+        for day in 2..5 {
+            schedule_of = schedule(Some(&schedule_of), Grade::Easy, date(day));
+        }
+
+        let forgotten = schedule(Some(&schedule_of), Grade::Again, date(5));
+        assert_eq!((forgotten.interval, forgotten.repetitions), (1, 0));
+        assert!(forgotten.ease < schedule_of.ease);
+        assert!(schedule(None, Grade::Again, date(1)).ease >= super::MIN_EASE);
+        assert_eq!(Grade::parse(" E\n"), Some(Grade::Easy));
+        assert_eq!(Grade::parse("later"), None);
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{config::Config, date::parse_date, error::Error, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Rm {
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+
+    /// Remove the note without asking for confirmation
+    #[clap(short, long)]
+    pub force: bool,
+}
+
+impl Rm {
+    /// Removes a note, asking for confirmation unless `--force` is passed.
+    ///
+    /// The date directory is removed as well once it no longer holds any files.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotFindFile` - If the note does not exist.
+    /// * `Error::CannotReadStdin` - If the confirmation cannot be read.
+    /// * `Error::CannotRemoveFile` - If the note cannot be removed.
+    pub fn remove(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let config = Config::load()?;
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        let path = notes::note_path(root_dir, date, title)?;
+
+        if !path.exists() {
+            return Err(Error::CannotFindFile(path));
+        }
+
+        if !self.force && !confirm(&format!("remove {}?", path.display()))? {
+            return Ok(());
+        }
+
+        fs::remove_file(&path).map_err(|err| Error::CannotRemoveFile(path.clone(), err))?;
+
+        if let Some(directory) = path.parent() {
+            notes::remove_dir_if_empty(directory)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Asks the user a yes or no question on stderr, defaulting to no.
+fn confirm(question: &str) -> crate::error::Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush().map_err(|_| Error::CannotReadStdin)?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .map_err(|_| Error::CannotReadStdin)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
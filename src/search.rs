@@ -1,32 +1,658 @@
-use std::{fs, path::Path};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
 
+use chrono::Local;
 use clap::Args;
+use serde::Serialize;
 
-use crate::find_root_dir;
+use crate::{
+    config::Config,
+    date::{self, parse_date},
+    error::Error,
+    notes::{self, notes_in, Day, Note},
+    page::Page,
+    style,
+};
 
-#[derive(Args, Debug)]
+/// How similar a title must be to the requested one to be taken for it, from 0 to 1.
+const SIMILARITY: f64 = 0.6;
+
+#[derive(Args, Debug, Default)]
+#[group(skip)]
 pub struct Search {
-    /// Specify an exact date ("MM-DD-YYY")
-    #[clap(long, group("search"))]
+    /// Only show bullets containing this text, ignoring case, across every date
+    /// unless dates are given
+    pub query: Option<String>,
+
+    /// Specify an exact date ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
     pub date: Option<String>,
+
+    /// Show notes from this date onwards ("MM-DD-YYYY")
+    #[clap(long, conflicts_with = "date")]
+    pub from: Option<String>,
+
+    /// Show notes up to this date ("MM-DD-YYYY")
+    #[clap(long, conflicts_with = "date")]
+    pub to: Option<String>,
+
+    /// Show every note of every date, grouped by date
+    #[clap(long, conflicts_with_all = ["date", "from", "to", "query"])]
+    pub all: bool,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(long)]
+    pub title: Option<String>,
+
+    /// Show the note with the closest title when none has the given title
+    #[clap(long)]
+    pub fuzzy: bool,
+
+    /// Only show notes with at least one of the given tags
+    #[clap(long = "tag")]
+    pub tags: Vec<String>,
+
+    /// Only show notes filed under this topic directory, e.g. "rust/async"
+    #[clap(long)]
+    pub topic: Option<String>,
+
+    /// Print the notes as JSON
+    #[clap(long)]
+    pub json: bool,
+
+    /// Render the Markdown of the notes instead of printing it as is
+    #[clap(long, conflicts_with = "json")]
+    pub render: bool,
+
+    /// Also search the date directories moved aside with `til archive`
+    #[clap(long)]
+    pub include_archived: bool,
+
+    #[clap(flatten)]
+    pub page: Page,
+}
+
+/// A note matching a search, along with the date it was found under.
+struct Found {
+    date: String,
+    name: String,
+    contents: String,
+    note: Note,
+}
+
+/// The JSON representation of a note, as printed with `--json`.
+#[derive(Serialize)]
+pub struct JsonNote<'a> {
+    date: &'a str,
+    title: String,
+    tags: Vec<String>,
+    bullets: Vec<String>,
 }
 
 impl Search {
-    pub fn by_date(date: String) -> Option<String> {
-        let root_dir = find_root_dir()?;
+    /// Retrieves the notes from a date range when `--from` or `--to` is given, every
+    /// note with `--all`, and the notes from a single date otherwise, writing them
+    /// to `out`.
+    ///
+    /// Notes are written styled for the terminal, grouped by date for ranges, or as
+    /// a JSON array of `{ date, title, tags, bullets }` objects with `--json`. With a
+    /// query, only the matching bullets are written instead. The notes of a range are
+    /// written as each date is read, so a wide range is never held in memory at once.
+    pub fn retrieve(&self, root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+        if let Some(query) = &self.query {
+            let matches = self.by_query(root_dir, query)?;
+            return writeln!(out, "{matches}").map_err(write_error);
+        }
 
-        let path = {
-            let mut path = Path::new(&root_dir).join(&date).join("default");
-            path.set_extension("md");
-            path
+        if self.all || self.from.is_some() || self.to.is_some() {
+            return self.by_date_range(root_dir, out);
+        }
+
+        let mut output = Output::new(self, out, false);
+        for note in self.page.apply(self.by_date(root_dir)?) {
+            output.write(&note)?;
+        }
+
+        output.finish()
+    }
+
+    /// Finds the bullets containing the query, ignoring case, within the notes
+    /// matching the requested title and tags.
+    ///
+    /// Every date is searched unless `--date`, `--from` or `--to` is given. Each match
+    /// is formatted as `MM-DD-YYYY/title.md: - bullet`, or the matching notes are
+    /// printed as JSON with only their matching bullets with `--json`.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If a date cannot be parsed.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::NoMatchesFound` - If no bullet contains the query.
+    fn by_query(&self, root_dir: &Path, query: &str) -> crate::error::Result<String> {
+        let lowercase = query.to_lowercase();
+        let tags = self.requested_tags();
+        let today = Local::now().date_naive();
+        let (from, to) = match &self.date {
+            Some(date) => {
+                let date = parse_date(date)?;
+                (Some(date), Some(date))
+            }
+            None => (
+                self.from.as_deref().map(parse_date).transpose()?,
+                Some(
+                    self.to
+                        .as_deref()
+                        .map(parse_date)
+                        .transpose()?
+                        .unwrap_or(today),
+                ),
+            ),
         };
 
-        let directory = path.parent()?;
+        let mut found = Vec::new();
+        for day in self.days(root_dir)? {
+            if from.is_some_and(|from| day.date < from) || to.is_some_and(|to| day.date > to) {
+                continue;
+            }
+
+            let directory = self.directory(&day.path)?;
+            if !directory.exists() {
+                continue;
+            }
+            let paths = match &self.title {
+                Some(title) => vec![notes::note_in(&directory, title)?],
+                None => notes_in(&directory)?,
+            };
+
+            for path in paths.into_iter().filter(|path| path.exists()) {
+                let contents = notes::read(&path)?;
+
+                if tags.is_empty() || has_any_tag(&contents, &tags) {
+                    found.push(Found {
+                        date: day.name(),
+                        name: day.note_name(&path),
+                        note: Note::parse(day.date, &path, &contents),
+                        contents,
+                    });
+                }
+            }
+        }
+
+        let matches = |bullet: &String| bullet.to_lowercase().contains(&lowercase);
+
+        if self.json {
+            let notes = found
+                .iter()
+                .map(|note| {
+                    to_json_note(
+                        note,
+                        note.note
+                            .bullets
+                            .iter()
+                            .filter(|bullet| matches(bullet))
+                            .cloned()
+                            .collect(),
+                    )
+                })
+                .filter(|note| !note.bullets.is_empty())
+                .collect::<Vec<_>>();
+
+            if notes.is_empty() {
+                return Err(Error::NoMatchesFound(query.to_owned()));
+            }
+            let notes = self.page.apply(notes);
+
+            return serde_json::to_string_pretty(&notes)
+                .map_err(|err| Error::Custom(err.to_string()));
+        }
+
+        let mut lines = Vec::new();
+        for note in &found {
+            note.note
+                .bullets
+                .iter()
+                .filter(|bullet| matches(bullet))
+                .for_each(|bullet| lines.push(format!("{}: - {bullet}", note.name)));
+        }
 
+        if lines.is_empty() {
+            return Err(Error::NoMatchesFound(query.to_owned()));
+        }
+
+        Ok(self.page.apply(lines).join("\n"))
+    }
+
+    /// Finds the notes from a specific date.
+    ///
+    /// The date defaults to today when none is provided. When a title is given only
+    /// that note is considered, otherwise every note of the date is considered when
+    /// filtering by tags and the "default" note is used when not.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::NoNotesFound` - If there is no directory for the date or no note has a requested tag.
+    /// * `Error::CannotFindFile` - If the date has no note with the given title.
+    /// * `Error::CannotReadFile` - If a note cannot be read.
+    fn by_date(&self, root_dir: &Path) -> crate::error::Result<Vec<Found>> {
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let day = Day {
+            date,
+            path: notes::day_path(root_dir, date)?,
+        };
+        let day = self.located(day, root_dir)?;
+
+        if !self.directory(&day.path)?.exists() {
+            return Err(Error::NoNotesFound(day.name()));
+        }
+
+        let config = Config::load()?;
+        let found = self.notes_from(&day, config.default_title(), true)?;
+
+        if found.is_empty() {
+            return Err(Error::NoNotesFound(day.name()));
+        }
+
+        Ok(found)
+    }
+
+    /// Writes the notes from every date within an inclusive range to `out`, sorted
+    /// by date.
+    ///
+    /// The range starts at the earliest note when `--from` is omitted and ends today
+    /// when `--to` is omitted, or at the latest note with `--all`. Every note of each
+    /// date is read with `--all`, and dates without a matching note are skipped.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If a date cannot be parsed.
+    /// * `Error::NoNotesFound` - If no note within the range matches.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotWriteToFile` - If the notes cannot be written.
+    fn by_date_range(&self, root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+        let days = self.days(root_dir)?;
+        let today = Local::now().date_naive();
+
+        let from = match &self.from {
+            Some(date) => parse_date(date)?,
+            None => days.first().map_or(today, |day| day.date),
+        };
+        let to = match &self.to {
+            Some(date) => parse_date(date)?,
+            None if self.all => days.last().map_or(today, |day| day.date.max(today)),
+            None => today,
+        };
+
+        let config = Config::load()?;
+        let mut output = Output::new(self, out, true);
+        let mut total = 0;
+        for day in days.iter().filter(|day| (from..=to).contains(&day.date)) {
+            for note in self.notes_from(day, config.default_title(), false)? {
+                if self.page.contains(total) {
+                    output.write(&note)?;
+                }
+                total += 1;
+            }
+        }
+
+        if total == 0 && self.all && self.from.is_none() && self.to.is_none() {
+            return Err(Error::NoNotesFound("any date".to_owned()));
+        }
+        if total == 0 {
+            return Err(Error::NoNotesFound(format!(
+                "{} to {}",
+                date::display_date(from, &config),
+                date::display_date(to, &config)
+            )));
+        }
+        self.page.report(total);
+
+        output.finish()
+    }
+
+    /// Lists the date directories to search, along with the archived ones with
+    /// `--include-archived`, sorted by date.
+    fn days(&self, root_dir: &Path) -> crate::error::Result<Vec<Day>> {
+        let mut days = notes::days(root_dir)?;
+        if self.include_archived {
+            days.extend(notes::days(&notes::archive_dir_in(root_dir))?);
+            days.sort_by_key(|day| day.date);
+        }
+
+        Ok(days)
+    }
+
+    /// Falls back to the archived directory of a date missing from the notes
+    /// directory with `--include-archived`, returning the day as is otherwise.
+    fn located(&self, day: Day, root_dir: &Path) -> crate::error::Result<Day> {
+        if !self.include_archived || day.path.exists() {
+            return Ok(day);
+        }
+
+        let archived = notes::days(&notes::archive_dir_in(root_dir))?
+            .into_iter()
+            .find(|archived| archived.date == day.date);
+        Ok(archived.unwrap_or(day))
+    }
+
+    /// Reads the notes of a date directory which match the requested topic, title
+    /// and tags. Every note of the date is read when filtering by tags or with
+    /// `--all`, unless a title is given.
+    ///
+    /// A missing title is an error when `require_title` is set and is skipped otherwise.
+    fn notes_from(
+        &self,
+        day: &Day,
+        default_title: &str,
+        require_title: bool,
+    ) -> crate::error::Result<Vec<Found>> {
+        let directory = self.directory(&day.path)?;
         if !directory.exists() {
-            return None;
+            return Ok(Vec::new());
         }
 
-        fs::read_to_string(path).ok()
+        let tags = self.requested_tags();
+        let paths = match &self.title {
+            None if self.all || !tags.is_empty() => notes_in(&directory)?,
+            title => {
+                let path = notes::note_in(&directory, title.as_deref().unwrap_or(default_title))?;
+
+                if path.exists() {
+                    vec![path]
+                } else if !require_title {
+                    return Ok(Vec::new());
+                } else {
+                    vec![self.closest_note(&directory, path)?]
+                }
+            }
+        };
+
+        let mut found = Vec::new();
+        for path in paths {
+            let contents = notes::read(&path)?;
+
+            if tags.is_empty() || has_any_tag(&contents, &tags) {
+                found.push(Found {
+                    date: day.name(),
+                    name: day.note_name(&path),
+                    note: Note::parse(day.date, &path, &contents),
+                    contents,
+                });
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Returns the note of a directory whose title is the closest to the one of a
+    /// missing note, when close enough to be a typo.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotFindNote` - If a note is close enough without `--fuzzy`, suggesting it.
+    /// * `Error::CannotFindFile` - If no note is close enough.
+    /// * `Error::CannotReadFile` - If the directory cannot be read.
+    fn closest_note(&self, directory: &Path, missing: PathBuf) -> crate::error::Result<PathBuf> {
+        let notes = notes_in(directory)?
+            .into_iter()
+            .filter(|note| note.parent() == Some(directory))
+            .collect::<Vec<_>>();
+        let stems = notes.iter().map(|note| stem(note)).collect::<Vec<_>>();
+
+        let Some(index) = closest(&stem(&missing), &stems) else {
+            return Err(Error::CannotFindFile(missing));
+        };
+        if !self.fuzzy {
+            return Err(Error::CannotFindNote(missing, stems[index].clone()));
+        }
+
+        eprintln!(
+            "showing \"{}\", the closest title to \"{}\"",
+            stems[index],
+            stem(&missing)
+        );
+        Ok(notes[index].clone())
+    }
+
+    /// Returns the directory of the requested topic within a date directory, or the
+    /// date directory itself when no topic is requested.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTopic` - If the topic cannot be used as a directory.
+    fn directory(&self, day_path: &Path) -> crate::error::Result<PathBuf> {
+        match &self.topic {
+            Some(topic) => notes::topic_dir(day_path, topic),
+            None => Ok(day_path.to_path_buf()),
+        }
+    }
+
+    /// Styles a note for the terminal, rendering its Markdown with `--render`.
+    fn display(&self, contents: &str) -> String {
+        if self.render {
+            style::rendered_note(contents)
+        } else {
+            style::note(contents.trim())
+        }
+    }
+
+    /// Returns the requested tags, trimmed and lowercased, without any empty tags.
+    fn requested_tags(&self) -> Vec<String> {
+        self.tags
+            .iter()
+            .map(|tag| tag.trim().to_lowercase())
+            .filter(|tag| !tag.is_empty())
+            .collect()
+    }
+}
+
+/// Writes found notes one at a time, either styled for the terminal or as the
+/// elements of a pretty-printed JSON array.
+struct Output<'a, W: Write> {
+    search: &'a Search,
+    out: &'a mut W,
+    grouped: bool,
+    written: usize,
+    previous_date: Option<String>,
+}
+
+impl<'a, W: Write> Output<'a, W> {
+    /// Starts writing notes to `out`, headed by their date when `grouped`.
+    fn new(search: &'a Search, out: &'a mut W, grouped: bool) -> Self {
+        Output {
+            search,
+            out,
+            grouped,
+            written: 0,
+            previous_date: None,
+        }
+    }
+
+    /// Writes a note after the ones written so far.
+    fn write(&mut self, note: &Found) -> crate::error::Result<()> {
+        if self.search.json {
+            let json = serde_json::to_string_pretty(&to_json_note(note, note.note.bullets.clone()))
+                .map_err(|err| Error::Custom(err.to_string()))?;
+            let separator = if self.written == 0 { "[\n" } else { ",\n" };
+            write!(self.out, "{separator}{}", indent(&json)).map_err(write_error)?;
+        } else {
+            if self.grouped && self.previous_date.as_ref() != Some(&note.date) {
+                if self.written > 0 {
+                    write!(self.out, "\n\n").map_err(write_error)?;
+                }
+                write!(
+                    self.out,
+                    "{}\n\n",
+                    style::heading(&format!("# {}", date::display(note.note.date, &note.date)))
+                )
+                .map_err(write_error)?;
+                self.previous_date = Some(note.date.clone());
+            } else if self.written > 0 {
+                write!(self.out, "\n\n").map_err(write_error)?;
+            }
+            write!(
+                self.out,
+                "{}",
+                self.search.display(&note.contents).trim_end()
+            )
+            .map_err(write_error)?;
+        }
+
+        self.written += 1;
+        Ok(())
+    }
+
+    /// Ends the output, closing the JSON array of the notes.
+    fn finish(self) -> crate::error::Result<()> {
+        let end = match (self.search.json, self.written) {
+            (true, 0) => "[]\n",
+            (true, _) => "\n]\n",
+            (false, _) => "\n",
+        };
+
+        write!(self.out, "{end}")
+            .and_then(|_| self.out.flush())
+            .map_err(write_error)
+    }
+}
+
+/// Indents every line of a pretty-printed JSON value to nest it within an array.
+fn indent(json: &str) -> String {
+    json.lines()
+        .map(|line| format!("  {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn write_error(err: io::Error) -> Error {
+    Error::CannotWriteToFile("stdout".into(), err)
+}
+
+/// Builds the JSON representation of a found note with the given bullets.
+fn to_json_note(found: &Found, bullets: Vec<String>) -> JsonNote<'_> {
+    json_note(&found.date, &found.note, bullets)
+}
+
+/// Builds the JSON representation of a note, dated with the name of its date
+/// directory, with the given bullets.
+pub fn json_note<'a>(date: &'a str, note: &Note, bullets: Vec<String>) -> JsonNote<'a> {
+    JsonNote {
+        date,
+        title: note.title.clone(),
+        tags: note.tags.clone(),
+        bullets,
+    }
+}
+
+/// Checks whether the front matter of a note contains any of the given tags.
+///
+/// Tags are compared case-insensitively, and notes without a `tags` field are
+/// treated as having no tags at all.
+fn has_any_tag(contents: &str, tags: &[String]) -> bool {
+    notes::tags(contents)
+        .iter()
+        .any(|tag| tags.contains(&tag.to_lowercase()))
+}
+
+/// Returns the file name of a note without its extension.
+fn stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Returns the index of the name most similar to a requested one, ignoring case,
+/// when similar enough to be taken for it.
+fn closest(requested: &str, names: &[String]) -> Option<usize> {
+    let requested = requested.to_lowercase();
+
+    names
+        .iter()
+        .map(|name| strsim::normalized_levenshtein(&requested, &name.to_lowercase()))
+        .enumerate()
+        .filter(|(_, similarity)| *similarity >= SIMILARITY)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::{closest, has_any_tag, Search};
+    use crate::{date::parse_date, notes::Day};
+
+    #[test]
+    fn tags_are_matched_case_insensitively() {
+        let contents = "---\ntitle: \"default\"\ntags: [Rust, async]\n---\n\n- note\n";
+
+        assert!(has_any_tag(contents, &["rust".to_string()]));
+        assert!(has_any_tag(
+            contents,
+            &["clap".to_string(), "async".to_string()]
+        ));
+        assert!(!has_any_tag(contents, &["clap".to_string()]));
+    }
+
+    #[test]
+    fn notes_without_tags_never_match() {
+        let empty = "---\ntitle: \"default\"\ntags: []\n---\n\n- note\n";
+        let missing = "---\ntitle: \"default\"\n---\n\n- note\n";
+
+        assert!(!has_any_tag(empty, &["rust".to_string()]));
+        assert!(!has_any_tag(missing, &["rust".to_string()]));
+    }
+
+    #[test]
+    fn titles_close_enough_are_suggested() {
+        let names = ["database", "rust-async", "default"].map(str::to_owned);
+
+        assert_eq!(closest("databse", &names), Some(0));
+        assert_eq!(closest("Rust-Asnyc", &names), Some(1));
+        assert_eq!(closest("cooking", &names), None);
+        assert_eq!(closest("databse", &[]), None);
+    }
+
+    #[test]
+    fn archived_dates_are_only_searched_when_included() {
+        let root_dir = env::temp_dir().join(format!("til-search-archived-{}", std::process::id()));
+        fs::create_dir_all(root_dir.join("03-01-2024")).unwrap();
+        fs::create_dir_all(root_dir.join("archive").join("01-15-2024")).unwrap();
+        let day = || Day {
+            date: parse_date("01-15-2024").unwrap(),
+            path: root_dir.join("01-15-2024"),
+        };
+        let names = |search: &Search| {
+            search
+                .days(&root_dir)
+                .unwrap()
+                .iter()
+                .map(|day| day.name())
+                .collect::<Vec<_>>()
+        };
+
+        let live = Search::default();
+        let archived = Search {
+            include_archived: true,
+            ..Search::default()
+        };
+        let live_days = names(&live);
+        let all_days = names(&archived);
+        let live_day = live.located(day(), &root_dir).unwrap();
+        let archived_day = archived.located(day(), &root_dir).unwrap();
+        fs::remove_dir_all(&root_dir).unwrap();
+
+        assert_eq!(live_days, ["03-01-2024"]);
+        assert_eq!(all_days, ["01-15-2024", "03-01-2024"]);
+        assert_eq!(live_day.path, root_dir.join("01-15-2024"));
+        assert_eq!(
+            archived_day.path,
+            root_dir.join("archive").join("01-15-2024")
+        );
     }
 }
@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use chrono::NaiveDate;
+use clap::Args;
+use serde_json::json;
+
+use crate::{
+    config::Config,
+    date::{self, parse_date},
+    index::{Index, IndexedNote},
+    notes,
+};
+
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Stats {
+    /// Print the summary as JSON
+    #[clap(long)]
+    pub json: bool,
+
+    /// Only summarize the notes from this date on ("MM-DD-YYYY", "30 days ago", ...)
+    #[clap(long)]
+    pub since: Option<String>,
+
+    /// Only summarize the notes up to this date ("MM-DD-YYYY", "yesterday", ...)
+    #[clap(long)]
+    pub until: Option<String>,
+}
+
+impl Stats {
+    /// Summarizes the activity of every note, or of the notes within the window set
+    /// by `--since` and `--until`.
+    ///
+    /// The summary includes the number of notes and note days, the first and most
+    /// recent dates with notes, the average number of notes per day with notes, the
+    /// longest streak of consecutive days with notes, the number of words across
+    /// every note along with the time it takes to read them, and the longest note.
+    /// Streaks only count the days within the window.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If a date of the window cannot be parsed.
+    /// * `Error::CannotReadFile` - If the notes directory, a date directory or a note cannot be read while indexing.
+    /// * `Error::CannotDecrypt` - If an encrypted note cannot be decrypted.
+    pub fn summary(&self, root_dir: &Path) -> crate::error::Result<String> {
+        let since = self.since.as_deref().map(parse_date).transpose()?;
+        let until = self.until.as_deref().map(parse_date).transpose()?;
+        let notes = Index::load(root_dir)?
+            .notes
+            .into_iter()
+            .filter(|note| within(note.date, since, until))
+            .map(IndexedNote::opened)
+            .collect::<crate::error::Result<Vec<_>>>()?;
+        let mut dates = notes.iter().map(|note| note.date).collect::<Vec<_>>();
+        dates.dedup();
+
+        let total = notes.len();
+        let words = notes.iter().map(|note| note.words).sum::<usize>();
+        let mut longest: Option<(usize, String)> = None;
+        for note in &notes {
+            if longest.as_ref().is_none_or(|(most, _)| note.words > *most) {
+                longest = Some((note.words, note.name.clone()));
+            }
+        }
+
+        let config = Config::load()?;
+        let stored = |date: &NaiveDate| config.date_format.format(*date);
+        let first = dates.first().map(stored);
+        let last = dates.last().map(stored);
+        let average = if dates.is_empty() {
+            0.0
+        } else {
+            total as f64 / dates.len() as f64
+        };
+        let streak = longest_streak(&dates);
+        let reading_time = words.div_ceil(WORDS_PER_MINUTE);
+
+        if self.json {
+            let summary = json!({
+                "notes": total,
+                "days": dates.len(),
+                "first": first,
+                "last": last,
+                "average": average,
+                "longest_streak": streak,
+                "words": words,
+                "reading_minutes": reading_time,
+                "longest_note": longest.map(|(count, note)| json!({ "note": note, "words": count })),
+            });
+
+            return Ok(summary.to_string());
+        }
+
+        let displayed = |date: Option<&NaiveDate>| {
+            date.map_or_else(
+                || "-".to_owned(),
+                |date| date::display(*date, &stored(date)),
+            )
+        };
+        let rows = [
+            ("notes", total.to_string()),
+            ("days", dates.len().to_string()),
+            ("first", displayed(dates.first())),
+            ("most recent", displayed(dates.last())),
+            ("notes per day", format!("{average:.2}")),
+            ("longest streak", format!("{streak} days")),
+            ("words", words.to_string()),
+            ("reading time", format!("{reading_time} min")),
+            (
+                "longest note",
+                longest
+                    .map(|(count, note)| format!("{note} ({count} words)"))
+                    .unwrap_or_else(|| "-".to_owned()),
+            ),
+        ];
+        let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+
+        Ok(rows
+            .iter()
+            .map(|(key, value)| format!("{key:width$}  {value}"))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Counts the words within the body of a note, leaving out its front matter and
+/// bullet prefixes.
+pub fn count_words(contents: &str) -> usize {
+    notes::body(contents)
+        .lines()
+        .map(|line| notes::strip_bullet(line.trim_start()).unwrap_or(line))
+        .map(|line| line.split_whitespace().count())
+        .sum()
+}
+
+/// Checks whether a date falls within an inclusive window, open on any side without
+/// a bound.
+fn within(date: NaiveDate, since: Option<NaiveDate>, until: Option<NaiveDate>) -> bool {
+    since.is_none_or(|since| date >= since) && until.is_none_or(|until| date <= until)
+}
+
+/// Returns the length of the longest run of consecutive dates.
+///
+/// The dates are expected to be sorted.
+fn longest_streak(dates: &[NaiveDate]) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    let mut previous: Option<NaiveDate> = None;
+
+    for &date in dates {
+        current = match previous {
+            Some(previous) if previous == date => current,
+            Some(previous) if previous.succ_opt() == Some(date) => current + 1,
+            _ => 1,
+        };
+        longest = longest.max(current);
+        previous = Some(date);
+    }
+
+    longest
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{count_words, longest_streak, within};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn longest_streak_spans_month_boundaries() {
+        let dates = [
+            date(9, 1),
+            date(9, 29),
+            date(9, 30),
+            date(10, 1),
+            date(10, 3),
+        ];
+
+        assert_eq!(longest_streak(&dates), 3);
+        assert_eq!(longest_streak(&[]), 0);
+    }
+
+    #[test]
+    fn streaks_are_counted_within_the_window() {
+        let dates = [
+            date(9, 28),
+            date(9, 29),
+            date(9, 30),
+            date(10, 1),
+            date(10, 5),
+        ];
+        let windowed = dates
+            .into_iter()
+            .filter(|&day| within(day, Some(date(9, 30)), None))
+            .collect::<Vec<_>>();
+
+        assert_eq!(windowed, [date(9, 30), date(10, 1), date(10, 5)]);
+        assert_eq!(longest_streak(&windowed), 2);
+        assert!(within(date(9, 1), None, Some(date(9, 1))));
+        assert!(!within(date(9, 2), None, Some(date(9, 1))));
+    }
+
+    #[test]
+    fn words_leave_out_front_matter_and_bullets() {
+        let contents = "---\ntitle: \"rust\"\ntags: [a, b]\n---\n\n- futures are lazy\n- pin  it\n";
+
+        assert_eq!(count_words(contents), 5);
+    }
+}
@@ -0,0 +1,197 @@
+use owo_colors::{OwoColorize, Stream::Stdout, Style};
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+
+use crate::{front_matter::FrontMatter, notes};
+
+/// Styles a note for the terminal, with its title in bold, the rest of its front
+/// matter dimmed and its bullet markers in cyan.
+///
+/// Colors are left out when stdout isn't a terminal, `NO_COLOR` is set or
+/// `--no-color` is passed, in which case the note is returned as is.
+pub fn note(contents: &str) -> String {
+    let mut in_front_matter = false;
+    let mut lines = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        if line == "---" && (index == 0 || in_front_matter) {
+            in_front_matter = index == 0;
+            lines.push(dimmed(line));
+        } else if in_front_matter {
+            match line.strip_prefix("title:") {
+                Some(title) => lines.push(format!(
+                    "{}{}",
+                    dimmed("title:"),
+                    title.if_supports_color(Stdout, |text| text.bold())
+                )),
+                None => lines.push(dimmed(line)),
+            }
+        } else if let Some(bullet) = notes::strip_bullet(line) {
+            let marker = &line[..line.len() - bullet.len()];
+            lines.push(format!(
+                "{}{}",
+                marker.if_supports_color(Stdout, |text| text.cyan()),
+                bullet
+            ));
+        } else {
+            lines.push(line.to_owned());
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Renders a note for the terminal, with its title and tags on top and the Markdown
+/// of its body interpreted: bold, italics, inline code and links are styled, and
+/// other elements are shown as plain text.
+pub fn rendered_note(contents: &str) -> String {
+    let (meta, body) =
+        FrontMatter::split(contents).unwrap_or_else(|_| (FrontMatter::default(), contents));
+    let mut output = String::new();
+
+    if !meta.title.is_empty() {
+        output.push_str(&heading(&meta.title));
+        output.push('\n');
+    }
+    if !meta.tags.is_empty() {
+        output.push_str(&dimmed(&meta.tags.join(", ")));
+        output.push('\n');
+    }
+    if !output.is_empty() {
+        output.push('\n');
+    }
+
+    output.push_str(render_markdown(body).trim_end());
+    output
+}
+
+/// Renders Markdown as styled terminal text.
+fn render_markdown(markdown: &str) -> String {
+    let mut output = String::new();
+    let mut styles = vec![Style::new()];
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut links = Vec::new();
+
+    let push_styled = |output: &mut String, text: &str, style: Style| {
+        output.push_str(
+            &text
+                .if_supports_color(Stdout, |text| text.style(style))
+                .to_string(),
+        )
+    };
+
+    for event in Parser::new(markdown) {
+        let style = *styles.last().unwrap_or(&Style::new());
+
+        match event {
+            Event::Start(Tag::Strong) => styles.push(style.bold()),
+            Event::Start(Tag::Emphasis) => styles.push(style.italic()),
+            Event::Start(Tag::Strikethrough) => styles.push(style.strikethrough()),
+            Event::Start(Tag::Heading { .. }) => styles.push(style.bold()),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                links.push(dest_url);
+                styles.push(style.underline());
+            }
+            Event::Start(Tag::List(start)) => {
+                if !lists.is_empty() {
+                    output.push('\n');
+                }
+                lists.push(start);
+            }
+            Event::Start(Tag::Item) => {
+                let depth = lists.len().saturating_sub(1);
+                output.push_str(&"  ".repeat(depth));
+
+                let marker = match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}.", *number - 1)
+                    }
+                    _ => "-".to_owned(),
+                };
+                push_styled(&mut output, &marker, Style::new().cyan());
+                output.push(' ');
+            }
+            Event::End(TagEnd::Strong | TagEnd::Emphasis | TagEnd::Strikethrough) => {
+                styles.pop();
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                styles.pop();
+                output.push_str("\n\n");
+            }
+            Event::End(TagEnd::Link) => {
+                styles.pop();
+                if let Some(url) = links.pop() {
+                    push_styled(&mut output, &format!(" ({url})"), Style::new().dimmed());
+                }
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if lists.is_empty() {
+                    output.push('\n');
+                }
+            }
+            Event::End(TagEnd::Item) if !output.ends_with('\n') => output.push('\n'),
+            Event::End(TagEnd::Paragraph) => {
+                output.push('\n');
+                if lists.is_empty() {
+                    output.push('\n');
+                }
+            }
+            Event::Code(code) => push_styled(&mut output, &code, style.reversed()),
+            Event::Text(text) | Event::Html(text) | Event::InlineHtml(text) => {
+                push_styled(&mut output, &text, style)
+            }
+            Event::SoftBreak => output.push(' '),
+            Event::HardBreak => output.push('\n'),
+            Event::Rule => output.push_str("---\n\n"),
+            _ => {}
+        }
+    }
+
+    output
+}
+
+/// Styles a heading, such as the date grouping notes, in bold.
+pub fn heading(text: &str) -> String {
+    text.if_supports_color(Stdout, |text| text.bold())
+        .to_string()
+}
+
+/// Styles a mark telling that something succeeded.
+pub fn success(text: &str) -> String {
+    text.if_supports_color(Stdout, |text| text.green())
+        .to_string()
+}
+
+/// Styles a line which was added, such as a bullet only the later date has, in green.
+pub fn added(text: &str) -> String {
+    text.if_supports_color(Stdout, |text| text.green())
+        .to_string()
+}
+
+/// Styles a line which was removed, such as a bullet only the earlier date has, in red.
+pub fn removed(text: &str) -> String {
+    text.if_supports_color(Stdout, |text| text.red())
+        .to_string()
+}
+
+fn dimmed(text: &str) -> String {
+    text.if_supports_color(Stdout, |text| text.dimmed())
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rendered_note;
+
+    #[test]
+    fn markdown_renders_without_its_markup() {
+        owo_colors::set_override(false);
+        let contents = "---\ntitle: \"rust\"\ntags: [a, b]\n---\n\n- **bold** and _italic_\n- use `Box<T>`, see [the book](https://doc.rust-lang.org/book)\n";
+
+        assert_eq!(
+            rendered_note(contents),
+            "rust\na, b\n\n- bold and italic\n- use Box<T>, see the book (https://doc.rust-lang.org/book)"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use clap::{Args, ValueEnum};
+
+use crate::tags::count_tags;
+
+/// The width of the bar of the most frequent tag.
+const BAR_WIDTH: usize = 40;
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct TagCloud {
+    /// Leave out the tags carried by fewer notes
+    #[clap(long, default_value_t = 1)]
+    pub min_count: usize,
+
+    /// How to print the tags
+    #[clap(long, value_enum, default_value_t)]
+    pub format: CloudFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq)]
+pub enum CloudFormat {
+    /// A bar per tag, as long as its count relative to the most frequent tag
+    #[default]
+    Bars,
+    /// A "count<TAB>tag" line per tag, as read by word cloud generators
+    Weighted,
+}
+
+impl TagCloud {
+    /// Prints every tag carried by at least `--min-count` notes in descending
+    /// frequency, along with a bar sized by its count or in a format for word cloud
+    /// generators.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read while indexing.
+    pub fn render(&self, root_dir: &Path) -> crate::error::Result<String> {
+        let counts = count_tags(root_dir)?
+            .into_iter()
+            .filter(|(_, count)| *count >= self.min_count)
+            .collect::<Vec<_>>();
+
+        Ok(match self.format {
+            CloudFormat::Bars => to_bars(&counts),
+            CloudFormat::Weighted => counts
+                .iter()
+                .map(|(tag, count)| format!("{count}\t{tag}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        })
+    }
+}
+
+/// Renders a bar per tag, scaled so that the most frequent tag gets the full width
+/// and every tag gets at least one block.
+fn to_bars(counts: &[(String, usize)]) -> String {
+    let most = counts.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let width = counts.iter().map(|(tag, _)| tag.len()).max().unwrap_or(0);
+
+    counts
+        .iter()
+        .map(|(tag, count)| {
+            let length = (count * BAR_WIDTH).div_ceil(most);
+            format!("{tag:width$}  {} {count}", "█".repeat(length))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_bars;
+
+    #[test]
+    fn bars_are_scaled_to_the_most_frequent_tag() {
+        let counts = [
+            ("rust".to_string(), 4),
+            ("async".to_string(), 2),
+            ("wasm".to_string(), 1),
+        ];
+        let full = "█".repeat(40);
+        let half = "█".repeat(20);
+        let quarter = "█".repeat(10);
+
+        assert_eq!(
+            to_bars(&counts),
+            format!("rust   {full} 4\nasync  {half} 2\nwasm   {quarter} 1")
+        );
+        assert_eq!(to_bars(&[]), "");
+    }
+}
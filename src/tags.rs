@@ -0,0 +1,107 @@
+use std::{collections::HashMap, path::Path};
+
+use clap::Args;
+use serde_json::{Map, Value};
+
+use crate::{config::Config, front_matter::lowercase_tags, index::Index};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Tags {
+    /// Print the counts as a JSON object
+    #[clap(long)]
+    pub json: bool,
+
+    /// Only print the tags starting with this prefix, one per line, e.g. for shell completion
+    #[clap(long, value_name = "PREFIX", conflicts_with = "json")]
+    pub complete: Option<String>,
+}
+
+impl Tags {
+    /// Lists every tag used across all notes alongside the number of notes
+    /// carrying it, sorted by descending count. With `--complete`, only the names
+    /// of the tags starting with the prefix are listed, in the same order.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read while indexing.
+    pub fn counts(&self, root_dir: &Path) -> crate::error::Result<String> {
+        let counts = count_tags(root_dir)?;
+
+        if let Some(prefix) = &self.complete {
+            let prefix = if Config::load()?.case_insensitive_tags {
+                prefix.to_lowercase()
+            } else {
+                prefix.clone()
+            };
+            return Ok(completions(&counts, &prefix).join("\n"));
+        }
+
+        if self.json {
+            let object = counts
+                .into_iter()
+                .map(|(tag, count)| (tag, Value::from(count)))
+                .collect::<Map<_, _>>();
+
+            return Ok(Value::Object(object).to_string());
+        }
+
+        let width = counts.iter().map(|(tag, _)| tag.len()).max().unwrap_or(0);
+
+        Ok(counts
+            .iter()
+            .map(|(tag, count)| format!("{tag:width$}  {count}"))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+/// Returns the tags starting with a prefix.
+fn completions<'a>(counts: &'a [(String, usize)], prefix: &str) -> Vec<&'a str> {
+    counts
+        .iter()
+        .map(|(tag, _)| tag.as_str())
+        .filter(|tag| tag.starts_with(prefix))
+        .collect()
+}
+
+/// Counts the notes carrying each tag, sorted by descending count and then by name.
+/// With `case_insensitive_tags` configured, tags differing only by case are counted
+/// together in lowercase.
+pub fn count_tags(root_dir: &Path) -> crate::error::Result<Vec<(String, usize)>> {
+    let ignore_case = Config::load()?.case_insensitive_tags;
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for note in Index::load(root_dir)?.notes {
+        let mut tags = if ignore_case {
+            lowercase_tags(&note.tags)
+        } else {
+            note.tags
+        };
+        tags.sort();
+        tags.dedup();
+
+        tags.into_iter()
+            .for_each(|tag| *counts.entry(tag).or_default() += 1);
+    }
+
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::completions;
+
+    #[test]
+    fn completions_start_with_the_prefix() {
+        let counts =
+            [("rust", 3), ("async", 2), ("rustdoc", 1)].map(|(tag, count)| (tag.to_owned(), count));
+
+        assert_eq!(completions(&counts, "ru"), ["rust", "rustdoc"]);
+        assert_eq!(completions(&counts, ""), ["rust", "async", "rustdoc"]);
+        assert!(completions(&counts, "go").is_empty());
+    }
+}
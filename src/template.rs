@@ -0,0 +1,143 @@
+use std::{fs, path::Path, sync::LazyLock};
+
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::{
+    error::Error,
+    front_matter::{self, FrontMatter},
+};
+
+/// A `{{name}}` placeholder within a template.
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_]*)\s*\}\}").unwrap());
+
+/// The placeholders every template must hold, so that notes keep a title and tags.
+const REQUIRED: [&str; 2] = ["title", "tags"];
+
+/// The values the placeholders of a template are replaced with.
+pub struct Placeholders<'a> {
+    pub date: NaiveDate,
+    pub title: &'a str,
+    pub tags: &'a [String],
+    pub now: &'a str,
+}
+
+/// Reads a front matter template and renders it for a new note.
+///
+/// ## Errors
+///
+/// * `Error::CannotReadFile` - If the template cannot be read.
+/// * `Error::InvalidTemplate` - If the template lacks a required placeholder, holds an
+///   unknown one, or doesn't render to valid front matter.
+pub fn render_file(path: &Path, values: &Placeholders) -> crate::error::Result<FrontMatter> {
+    let template =
+        fs::read_to_string(path).map_err(|err| Error::CannotReadFile(path.to_path_buf(), err))?;
+
+    render(&template, values).map_err(|msg| Error::InvalidTemplate(path.to_path_buf(), msg))
+}
+
+/// Renders a front matter template, with or without its `---` delimiters.
+///
+/// `{{title}}`, `{{tags}}`, `{{date}}` and `{{created}}` are replaced with YAML
+/// values, quoted when needed, so they must not be quoted within the template.
+/// `{{tags}}` becomes a flow list such as `[rust, async]` and `{{date}}` the date of
+/// the note as "YYYY-MM-DD". Unless the template sets them, the note is created and
+/// updated now.
+fn render(template: &str, values: &Placeholders) -> Result<FrontMatter, String> {
+    let names = PLACEHOLDER
+        .captures_iter(template)
+        .map(|captures| captures[1].to_owned())
+        .collect::<Vec<_>>();
+    if let Some(missing) = REQUIRED
+        .iter()
+        .find(|required| !names.iter().any(|name| name == *required))
+    {
+        return Err(format!("no {{{{{missing}}}}} placeholder"));
+    }
+
+    let mut unknown = None;
+    let rendered =
+        PLACEHOLDER.replace_all(template, |captures: &regex::Captures| match &captures[1] {
+            "title" => front_matter::quoted(values.title),
+            "tags" => front_matter::flow_list(values.tags),
+            "date" => values.date.format("%Y-%m-%d").to_string(),
+            "created" => values.now.to_owned(),
+            name => {
+                unknown.get_or_insert_with(|| name.to_owned());
+                String::new()
+            }
+        });
+    if let Some(name) = unknown {
+        return Err(format!("unknown placeholder {{{{{name}}}}}"));
+    }
+
+    let yaml = rendered.trim();
+    let yaml = yaml
+        .strip_prefix("---")
+        .and_then(|yaml| yaml.strip_suffix("---"))
+        .unwrap_or(yaml);
+    let mut meta: FrontMatter =
+        serde_yaml::from_str(yaml).map_err(|err| format!("not valid YAML once rendered, {err}"))?;
+
+    if meta.created.is_none() {
+        meta.created = Some(values.now.to_owned());
+    }
+    if meta.updated.is_none() {
+        meta.updated = meta.created.clone();
+    }
+
+    Ok(meta)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+    use serde_yaml::Value;
+
+    use super::{render, Placeholders};
+
+    fn values<'a>(title: &'a str, tags: &'a [String]) -> Placeholders<'a> {
+        Placeholders {
+            date: NaiveDate::from_ymd_opt(2024, 10, 7).unwrap(),
+            title,
+            tags,
+            now: "2024-10-07T09:00:00+02:00",
+        }
+    }
+
+    #[test]
+    fn templates_render_placeholders_and_extra_fields() {
+        let tags = ["rust".to_owned(), "two words".to_owned()];
+        let template =
+            "---\ntitle: {{title}}\ntags: {{ tags }}\nday: {{date}}\nauthor: me\nmood:\n---\n";
+
+        let meta = render(template, &values("lifetimes: a tour", &tags)).unwrap();
+        assert_eq!(meta.title, "lifetimes: a tour");
+        assert_eq!(meta.tags, tags);
+        assert_eq!(meta.created.as_deref(), Some("2024-10-07T09:00:00+02:00"));
+        assert_eq!(meta.updated, meta.created);
+        assert_eq!(meta.extra["day"], Value::from("2024-10-07"));
+        assert_eq!(meta.extra["author"], Value::from("me"));
+        assert_eq!(meta.extra["mood"], Value::Null);
+    }
+
+    #[test]
+    fn templates_need_known_and_required_placeholders() {
+        let tags = [];
+
+        assert_eq!(
+            render("title: {{title}}\n", &values("rust", &tags)).unwrap_err(),
+            "no {{tags}} placeholder"
+        );
+        assert_eq!(
+            render(
+                "title: {{title}}\ntags: {{tags}}\nmood: {{mood}}\n",
+                &values("rust", &tags)
+            )
+            .unwrap_err(),
+            "unknown placeholder {{mood}}"
+        );
+        assert!(render("title: {{title}}\ntags: {{tags}}\n", &values("rust", &tags)).is_ok());
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs, path::Path};
+
+use chrono::Local;
+use clap::Args;
+
+use crate::{config::Config, date::parse_date, error::Error, front_matter::FrontMatter, notes};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Untag {
+    /// The tags to remove from the note
+    #[clap(required = true)]
+    pub tags: Vec<String>,
+
+    /// Specify the date of the note ("MM-DD-YYYY", "yesterday", ...), defaults to today
+    #[clap(long)]
+    pub date: Option<String>,
+
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+
+    /// Don't fail when the note lacks one of the tags
+    #[clap(long)]
+    pub ignore_missing: bool,
+}
+
+impl Untag {
+    /// Removes tags from the front matter of a note, keeping everything else as is.
+    /// Tags are matched ignoring case with `case_insensitive_tags` configured. A
+    /// note without front matter is left alone with a warning.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::CannotFindFile` - If the note does not exist.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::CannotParseMetaData` - If the front matter of the note cannot be parsed.
+    /// * `Error::TagNotFound` - If the note lacks a tag, unless `--ignore-missing` is passed.
+    /// * `Error::CannotWriteToFile` - If the note cannot be rewritten.
+    pub fn untag(&self, root_dir: &Path) -> crate::error::Result<()> {
+        let date = match &self.date {
+            Some(date) => parse_date(date)?,
+            None => Local::now().date_naive(),
+        };
+        let config = Config::load()?;
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        let path = notes::note_path(root_dir, date, title)?;
+
+        if !path.exists() {
+            return Err(Error::CannotFindFile(path));
+        }
+
+        let contents =
+            fs::read_to_string(&path).map_err(|err| Error::CannotReadFile(path.clone(), err))?;
+        if !contents.starts_with("---\n") {
+            eprintln!(
+                "warning: {} has no front matter, so it has no tags to remove",
+                path.display()
+            );
+            return Ok(());
+        }
+        let (mut meta, body) = FrontMatter::split(&contents)?;
+
+        let mut removed = false;
+        for tag in self.tags.iter().map(|tag| tag.trim()) {
+            if meta.remove_tag(tag, config.case_insensitive_tags) {
+                removed = true;
+            } else if !self.ignore_missing {
+                return Err(Error::TagNotFound(tag.to_owned(), path));
+            }
+        }
+
+        if !removed {
+            return Ok(());
+        }
+
+        notes::write_atomically(&path, &format!("{}{}", meta.render(), body))
+    }
+}
@@ -0,0 +1,148 @@
+use std::{fs, io::Write, path::Path, sync::mpsc};
+
+use chrono::Local;
+use clap::Args;
+use notify::{RecursiveMode, Watcher};
+
+use crate::{
+    config::Config,
+    error::Error,
+    notes::{self, Note},
+    style,
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Watch {
+    /// Specify the title of the note, defaults to "default"
+    #[clap(short, long)]
+    pub title: Option<String>,
+}
+
+/// What the watch loop is woken up by.
+enum Message {
+    Changed,
+    Stop,
+}
+
+impl Watch {
+    /// Writes today's note to `out`, then keeps watching it and writes every bullet
+    /// appended to it from then on, until interrupted with Ctrl-C. A note that
+    /// doesn't exist yet is waited for, and a note rewritten other than by appending
+    /// to it is written again in full.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::InvalidTitle` - If the title cannot be used as a file name.
+    /// * `Error::CannotCreateDir` - If the notes directory cannot be created.
+    /// * `Error::CannotReadFile` - If the note cannot be read.
+    /// * `Error::CannotWriteToFile` - If the note cannot be written to `out`.
+    pub fn watch(&self, root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+        let config = Config::load()?;
+        let title = self.title.as_deref().unwrap_or(config.default_title());
+        fs::create_dir_all(root_dir)
+            .map_err(|err| Error::CannotCreateDir(root_dir.display().to_string(), err))?;
+
+        let (sender, receiver) = mpsc::channel();
+        let changed = sender.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<_>| {
+            if event.is_ok() {
+                let _ = changed.send(Message::Changed);
+            }
+        })
+        .map_err(|err| Error::Custom(format!("cannot watch notes: {err}")))?;
+        watcher
+            .watch(root_dir, RecursiveMode::Recursive)
+            .map_err(|err| Error::Custom(format!("cannot watch {}: {err}", root_dir.display())))?;
+        ctrlc::set_handler(move || {
+            let _ = sender.send(Message::Stop);
+        })
+        .map_err(|err| Error::Custom(format!("cannot handle Ctrl-C: {err}")))?;
+
+        let mut printed: Option<Vec<String>> = None;
+        let mut waiting = false;
+        loop {
+            let path = notes::note_in(
+                &notes::day_path(root_dir, Local::now().date_naive())?,
+                title,
+            )?;
+            let name = path.strip_prefix(root_dir).unwrap_or(&path).to_path_buf();
+
+            match read_bullets(&path)? {
+                None if !waiting => {
+                    writeln!(out, "waiting for {} to be created", name.display())
+                        .map_err(|err| Error::CannotWriteToFile("stdout".into(), err))?;
+                    printed = None;
+                    waiting = true;
+                }
+                None => {}
+                // A note being rewritten can be read while still empty, the write
+                // completing it comes with an event of its own.
+                Some((contents, _)) if contents.trim().is_empty() => {}
+                Some((contents, bullets)) => {
+                    let update = match printed
+                        .as_deref()
+                        .map(|printed| appended(printed, &bullets))
+                    {
+                        Some(Some([])) => None,
+                        Some(Some(new)) => Some(notes::bullet_lines(new).trim_end().to_owned()),
+                        _ => Some(style::note(contents.trim())),
+                    };
+                    if let Some(update) = update {
+                        writeln!(out, "{update}")
+                            .map_err(|err| Error::CannotWriteToFile("stdout".into(), err))?;
+                    }
+                    printed = Some(bullets);
+                    waiting = false;
+                }
+            }
+            out.flush()
+                .map_err(|err| Error::CannotWriteToFile("stdout".into(), err))?;
+
+            match receiver.recv() {
+                Ok(Message::Changed) => {}
+                Ok(Message::Stop) | Err(_) => break,
+            }
+        }
+
+        watcher
+            .unwatch(root_dir)
+            .map_err(|err| Error::Custom(format!("cannot stop watching notes: {err}")))
+    }
+}
+
+/// Reads a note along with its bullets, or `None` while it doesn't exist.
+fn read_bullets(path: &Path) -> crate::error::Result<Option<(String, Vec<String>)>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = notes::read(path)?;
+    let bullets = Note::parse(Local::now().date_naive(), path, &contents).bullets;
+
+    Ok(Some((contents, bullets)))
+}
+
+/// Returns the bullets appended to a note since the `printed` ones, or `None` when
+/// the note was rewritten in some other way.
+fn appended<'a>(printed: &[String], bullets: &'a [String]) -> Option<&'a [String]> {
+    bullets
+        .starts_with(printed)
+        .then(|| &bullets[printed.len()..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::appended;
+
+    #[test]
+    fn only_appended_bullets_are_new() {
+        let printed = ["one", "two"].map(str::to_owned);
+        let bullets = ["one", "two", "three"].map(str::to_owned);
+        let amended = ["one", "three"].map(str::to_owned);
+
+        assert_eq!(appended(&printed, &bullets), Some(&bullets[2..]));
+        assert_eq!(appended(&printed, &printed), Some(&[][..]));
+        assert_eq!(appended(&printed, &amended), None);
+    }
+}
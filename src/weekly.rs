@@ -0,0 +1,188 @@
+use std::{io::Write, path::Path};
+
+use chrono::{Datelike, Days, Local, NaiveDate};
+use clap::Args;
+use serde::Serialize;
+
+use crate::{
+    config::Config,
+    date::{self, parse_date},
+    error::Error,
+    notes::{self, Note},
+    search::{self, JsonNote, Search},
+    style,
+};
+
+#[derive(Args, Debug)]
+#[group(skip)]
+pub struct Weekly {
+    /// Review the week, Monday to Sunday, holding this date instead of the last 7 days
+    #[clap(long)]
+    pub week_of: Option<String>,
+
+    /// Print the digest as JSON
+    #[clap(long)]
+    pub json: bool,
+
+    /// Render the Markdown of the notes instead of printing it as is
+    #[clap(long, conflicts_with = "json")]
+    pub render: bool,
+}
+
+/// The JSON representation of a digest.
+#[derive(Serialize)]
+struct Digest<'a> {
+    from: &'a str,
+    to: &'a str,
+    days: usize,
+    notes: usize,
+    bullets: usize,
+    tags: &'a [String],
+    entries: Vec<JsonNote<'a>>,
+}
+
+impl Weekly {
+    /// Writes a digest of the notes of the last 7 days, or of the week holding
+    /// `--week-of`, to `out`.
+    ///
+    /// The digest starts with the number of days, notes and bullets of the week along
+    /// with the distinct tags used, followed by every note grouped by date. With
+    /// `--json`, the same is written as a `{ from, to, days, notes, bullets, tags,
+    /// entries }` object.
+    ///
+    /// ## Errors
+    ///
+    /// * `Error::CannotParseDate` - If the date cannot be parsed.
+    /// * `Error::NoNotesFound` - If there is no note within the week.
+    /// * `Error::CannotReadFile` - If a note or directory cannot be read.
+    /// * `Error::CannotWriteToFile` - If the digest cannot be written.
+    pub fn digest(&self, root_dir: &Path, out: &mut impl Write) -> crate::error::Result<()> {
+        let config = Config::load()?;
+        let (from, to) = match &self.week_of {
+            Some(date) => week_of(parse_date(date)?),
+            None => {
+                let today = Local::now().date_naive();
+                (today - Days::new(6), today)
+            }
+        };
+        let mut days = Vec::new();
+        for day in notes::days(root_dir)? {
+            if (from..=to).contains(&day.date) {
+                for path in notes::notes_in(&day.path)? {
+                    let contents = notes::read(&path)?;
+                    days.push((day.name(), Note::parse(day.date, &path, &contents)));
+                }
+            }
+        }
+        let (shown_from, shown_to) = (
+            date::display_date(from, &config),
+            date::display_date(to, &config),
+        );
+        let (from, to) = (
+            config.date_format.format(from),
+            config.date_format.format(to),
+        );
+
+        if days.is_empty() {
+            return Err(Error::NoNotesFound(format!("{shown_from} to {shown_to}")));
+        }
+
+        let mut dates = days.iter().map(|(name, ..)| name).collect::<Vec<_>>();
+        dates.dedup();
+        let bullets = days
+            .iter()
+            .map(|(_, note)| note.bullets.len())
+            .sum::<usize>();
+        let tags = distinct_tags(days.iter().flat_map(|(_, note)| note.tags.iter().cloned()));
+
+        if self.json {
+            let entries = days
+                .iter()
+                .map(|(name, note)| search::json_note(name, note, note.bullets.clone()))
+                .collect::<Vec<_>>();
+            let digest = Digest {
+                from: &from,
+                to: &to,
+                days: dates.len(),
+                notes: days.len(),
+                bullets,
+                tags: &tags,
+                entries,
+            };
+
+            let digest = serde_json::to_string_pretty(&digest)
+                .map_err(|err| Error::Custom(err.to_string()))?;
+            return writeln!(out, "{digest}")
+                .map_err(|err| Error::CannotWriteToFile("stdout".into(), err));
+        }
+
+        let tags = if tags.is_empty() {
+            "-".to_owned()
+        } else {
+            tags.join(", ")
+        };
+        writeln!(
+            out,
+            "{}\n{} days, {} notes, {bullets} bullets\ntags: {tags}\n",
+            style::heading(&format!("# Week of {shown_from} to {shown_to}")),
+            dates.len(),
+            days.len(),
+        )
+        .map_err(|err| Error::CannotWriteToFile("stdout".into(), err))?;
+
+        Search {
+            from: Some(from),
+            to: Some(to),
+            all: true,
+            render: self.render,
+            ..Search::default()
+        }
+        .retrieve(root_dir, out)
+    }
+}
+
+/// Returns the Monday and the Sunday of the week holding a date.
+fn week_of(date: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let monday = date - Days::new(date.weekday().num_days_from_monday().into());
+    (monday, monday + Days::new(6))
+}
+
+/// Returns the tags sorted by name, leaving out the ones differing only by case
+/// from a tag already kept.
+fn distinct_tags(tags: impl Iterator<Item = String>) -> Vec<String> {
+    let mut distinct: Vec<String> = Vec::new();
+    for tag in tags {
+        if !distinct.iter().any(|kept| kept.eq_ignore_ascii_case(&tag)) {
+            distinct.push(tag);
+        }
+    }
+    distinct.sort_by_key(|tag| tag.to_lowercase());
+
+    distinct
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::{distinct_tags, week_of};
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    #[test]
+    fn weeks_run_from_monday_to_sunday() {
+        assert_eq!(week_of(date(10, 9)), (date(10, 7), date(10, 13)));
+        assert_eq!(week_of(date(10, 7)), (date(10, 7), date(10, 13)));
+        assert_eq!(week_of(date(10, 13)), (date(10, 7), date(10, 13)));
+        assert_eq!(week_of(date(10, 1)), (date(9, 30), date(10, 6)));
+    }
+
+    #[test]
+    fn tags_are_distinct_ignoring_case() {
+        let tags = ["rust", "async", "Rust", "clap"].map(str::to_owned);
+
+        assert_eq!(distinct_tags(tags.into_iter()), ["async", "clap", "rust"]);
+    }
+}